@@ -31,9 +31,18 @@ use std::time::*;
 use std::convert::{TryInto};
 use std::ops::{Add, Sub, AddAssign, SubAssign};
 use std::collections::HashMap;
-use std::sync::atomic::AtomicBool;
 
 use gloo_console::log;
+
+use crate::components::host::{AnimationLoop, EventListener, GameSession};
+
+use crate::renderer::gravity::{Attractor, GravityBuffer, GravityField};
+use crate::renderer::particle_system::{GpuParticleSystem, ParticleAttribute};
+use crate::renderer::report;
+use crate::renderer::shaders::{
+    ProgramBuilder, BARE_FRAG, BARE_VERT, BASIC_FRAG, PARTICLES_FRAG, PARTICLES_VERT,
+    TORPEDO_100_VERT, VEHICLE_100_VERT,
+};
 use std::f32::consts::PI;
 
 const AMORTIZATION: f32 = 0.95;
@@ -59,8 +68,8 @@ impl Instant {
     pub fn now() -> Self { Self(std::time::Instant::now()) }
     pub fn duration_since(&self, earlier: Instant) -> Duration { self.0.duration_since(earlier.0) }
     pub fn elapsed(&self) -> Duration { self.0.elapsed() }
-    pub fn checked_add(&self, duration: Duration) -> Option<Self> { self.0.checked_add(duration).map(|i| Self(i)) }
-    pub fn checked_sub(&self, duration: Duration) -> Option<Self> { self.0.checked_sub(duration).map(|i| Self(i)) }
+    pub fn checked_add(&self, duration: Duration) -> Option<Self> { self.0.checked_add(duration).map(Self) }
+    pub fn checked_sub(&self, duration: Duration) -> Option<Self> { self.0.checked_sub(duration).map(Self) }
 }
 
 #[cfg(target_arch = "wasm32")]
@@ -83,13 +92,13 @@ impl Instant {
     pub fn elapsed(&self) -> Duration { Self::now().duration_since(*self) }
     pub fn checked_add(&self, duration: Duration) -> Option<Self> {
         match duration.as_micros().try_into() {
-            Ok(duration) => self.0.checked_add(duration).map(|i| Self(i)),
+            Ok(duration) => self.0.checked_add(duration).map(Self),
             Err(_) => None,
         }
     }
     pub fn checked_sub(&self, duration: Duration) -> Option<Self> {
         match duration.as_micros().try_into() {
-            Ok(duration) => self.0.checked_sub(duration).map(|i| Self(i)),
+            Ok(duration) => self.0.checked_sub(duration).map(Self),
             Err(_) => None,
         }
     }
@@ -105,34 +114,38 @@ impl SubAssign<Duration> for Instant { fn sub_assign(&mut self, other: Duration)
 pub enum Msg {}
 
 pub struct GameFive {
-    node_ref: Arc<NodeRef>,
+    node_ref: NodeRef,
+    session: Option<GameSession>,
 }
 
 impl Component for GameFive {
     type Message = Msg;
     type Properties = ();
     fn create(_ctx: &Context<Self>) -> Self {
-        let node_ref = Arc::new(NodeRef::default());
         Self {
-            node_ref: node_ref,
+            node_ref: NodeRef::default(),
+            session: None,
         }
     }
     fn view(&self, _ctx: &Context<Self>) -> Html {
- 
         html! {
-            <canvas width=2000 height=2000 ref={(*self.node_ref).clone()} />
+            <canvas width=2000 height=2000 ref={self.node_ref.clone()} />
+        }
+    }
+    fn rendered(&mut self, _ctx: &Context<Self>, first_render: bool) {
+        if first_render {
+            self.session = Some(render_game(self.node_ref.clone()));
         }
     }
-    fn rendered(&mut self, ctx: &Context<Self>, _first_render: bool) {   
-        let alt_ref = (*self.node_ref).clone();
-        render_game(alt_ref);
+    fn destroy(&mut self, _ctx: &Context<Self>) {
+        self.session = None;
     }
 }
 
 fn render_game
 (
     node_ref: NodeRef,
-)
+) -> GameSession
 {
     let canvas = node_ref.cast::<HtmlCanvasElement>().unwrap();
     let canvas: web_sys::HtmlCanvasElement = canvas.dyn_into::<web_sys::HtmlCanvasElement>().unwrap();
@@ -145,148 +158,56 @@ fn render_game
         .unwrap();
     let gl : Arc<GL> = Arc::new(gl);
 
-    let (
-        player_vertex_buffer,
-        player_js_vertices,
-        player_shader_program,
-        player_vertices_position,
-        player_pos_deltas_loc,
-        player_vifo_theta_loc,
-        time_location,
-    ) = setup_shaders(gl.clone()).unwrap();
-
-    let (
-        torp_vertex_buffer,
-        torp_js_vertices,
-        torp_shader_program,
-        torp_vertices_position,
-        torp_pos_deltas_loc,
-        torp_vifo_theta_loc,
-        time_location_2,
-    ) = setup_torp_shaders(gl.clone()).unwrap();
-
-
-    let particles_shader_program = setup_particle_shaders(gl.clone()).unwrap();
+    let player_shape = setup_shaders(&gl).unwrap();
 
-    let mass_uniforms_location = Arc::new(Mutex::new(gl.get_uniform_block_index(&particles_shader_program, "Mass")));
+    let torp_shape = setup_torp_shaders(&gl).unwrap();
 
-    gl.uniform_block_binding(&particles_shader_program, *mass_uniforms_location.lock().unwrap(), 0);
 
-    let position_data : Arc<Mutex<[f32; (NUM_PARTICLES * 3) as usize]>> = Arc::new(Mutex::new([0.0; (NUM_PARTICLES * 3) as usize]));
-    let velocity_data : Arc<Mutex<[f32; (NUM_PARTICLES * 3) as usize]>> = Arc::new(Mutex::new([0.0; (NUM_PARTICLES *3) as usize]));
-    let color_data : Arc<Mutex<[f32; (NUM_PARTICLES * 3) as usize]>> = Arc::new(Mutex::new([0.0; (NUM_PARTICLES * 3) as usize]));
-
-    for i in 0..NUM_PARTICLES {
-        let vec3i : usize = (i as usize) * 3;
-
-        position_data.lock().unwrap()[vec3i] = (js_sys::Math::random() as f32) * LOCALIZED_SCALE - CORRECTION;
-        position_data.lock().unwrap()[vec3i + 1] = (js_sys::Math::random() as f32) * LOCALIZED_SCALE - CORRECTION;
-        position_data.lock().unwrap()[vec3i + 2] = (js_sys::Math::random() as f32) * LOCALIZED_SCALE - CORRECTION;
+    let particles_shader_program = setup_particle_shaders(gl.clone()).unwrap();
 
-        color_data.lock().unwrap()[vec3i] = js_sys::Math::random() as f32;
-        color_data.lock().unwrap()[vec3i + 1] = js_sys::Math::random() as f32;
-        color_data.lock().unwrap()[vec3i + 2] = js_sys::Math::random() as f32;
+    // position and velocity, interleaved, are advanced by transform feedback; color is fixed
+    let mut state_data: Vec<f32> = Vec::with_capacity((NUM_PARTICLES * 6) as usize);
+    let mut color_data: Vec<f32> = Vec::with_capacity((NUM_PARTICLES * 3) as usize);
+    for _ in 0..NUM_PARTICLES {
+        for _ in 0..3 {
+            state_data.push((js_sys::Math::random() as f32) * LOCALIZED_SCALE - CORRECTION);
+        }
+        state_data.extend_from_slice(&[0.0, 0.0, 0.0]);
+        for _ in 0..3 {
+            color_data.push(js_sys::Math::random() as f32);
+        }
     }
 
-    let vertex_array_a = Arc::new(gl.create_vertex_array().unwrap());
-    gl.bind_vertex_array(Some(&vertex_array_a));
-
-    let position_buffer_a = Arc::new(gl.create_buffer().unwrap());
-    gl.bind_buffer(GL::ARRAY_BUFFER, Some(&position_buffer_a));
-    let position_data_js = js_sys::Float32Array::from(position_data.lock().unwrap().as_slice());
-    gl.buffer_data_with_array_buffer_view(GL::ARRAY_BUFFER, &position_data_js, GL::STREAM_COPY);
-    gl.vertex_attrib_pointer_with_i32(0, 3, GL::FLOAT, false, 0, 0);
-    gl.enable_vertex_attrib_array(0);
-
-    let velocity_buffer_a = Arc::new(gl.create_buffer().unwrap());
-    gl.bind_buffer(GL::ARRAY_BUFFER, Some(&velocity_buffer_a));
-    let velocity_data_js = js_sys::Float32Array::from(velocity_data.lock().unwrap().as_slice());
-    gl.buffer_data_with_array_buffer_view(GL::ARRAY_BUFFER, &velocity_data_js, GL::STREAM_COPY);
-    gl.vertex_attrib_pointer_with_i32(1, 3, GL::FLOAT, false, 0, 0);
-    gl.enable_vertex_attrib_array(1);
-
-    let color_buffer = Arc::new(gl.create_buffer().unwrap());
-    gl.bind_buffer(GL::ARRAY_BUFFER, Some(&color_buffer));
-    let color_data_js = js_sys::Float32Array::from(color_data.lock().unwrap().as_slice());
-    gl.buffer_data_with_array_buffer_view(GL::ARRAY_BUFFER, &color_data_js, GL::STATIC_DRAW);
-    gl.vertex_attrib_pointer_with_i32(2, 3, GL::FLOAT, false, 0, 0);
-    gl.enable_vertex_attrib_array(2);
-
-    gl.bind_buffer(GL::ARRAY_BUFFER, None);
-
     // Transform feedback handles output 
     // https://github.com/tsherif/webgl2examples/blob/master/particles.html
+    let particle_system = GpuParticleSystem::new(&gl, NUM_PARTICLES as usize, &[
+        ParticleAttribute { location: 0, size: 3 }, // aPosition
+        ParticleAttribute { location: 1, size: 3 }, // aVelocity
+    ]).unwrap();
+    particle_system.write(&gl, 0, &state_data);
+    particle_system.add_static_attributes(&gl, &[ParticleAttribute { location: 2, size: 3 }], &color_data).unwrap();
+
+    // three masses scattered at random, fixed for the whole run
+    let gravity_field = GravityField {
+        attractors: (0..3).map(|_| Attractor {
+            position: [
+                (js_sys::Math::random() as f32) * LOCALIZED_SCALE - CORRECTION,
+                (js_sys::Math::random() as f32) * LOCALIZED_SCALE - CORRECTION,
+                (js_sys::Math::random() as f32) * LOCALIZED_SCALE - CORRECTION,
+            ],
+            mass: (js_sys::Math::random() / ((NUM_PARTICLES as f64) * 1.5)) as f32,
+        }).collect(),
+    };
+    let gravity_buffer = GravityBuffer::new(&gl).unwrap();
+    gravity_buffer.upload(&gl, &gravity_field);
 
-    let transform_feedback_a = Arc::new(gl.create_transform_feedback().unwrap());
-    gl.bind_transform_feedback(GL::TRANSFORM_FEEDBACK, Some(&transform_feedback_a));
-    gl.bind_buffer_base(GL::TRANSFORM_FEEDBACK_BUFFER, 0, Some(&position_buffer_a));
-    gl.bind_buffer_base(GL::TRANSFORM_FEEDBACK_BUFFER, 1, Some(&velocity_buffer_a));
-
-    let vertex_array_b = Arc::new(gl.create_vertex_array().unwrap());
-    gl.bind_vertex_array(Some(&vertex_array_b));
-
-    let position_buffer_b = Arc::new(gl.create_buffer().unwrap());
-    gl.bind_buffer(GL::ARRAY_BUFFER, Some(&position_buffer_b));
-    gl.buffer_data_with_array_buffer_view(GL::ARRAY_BUFFER, &position_data_js, GL::STREAM_COPY);
-    gl.vertex_attrib_pointer_with_i32(0, 3, GL::FLOAT, false, 0, 0);
-    gl.enable_vertex_attrib_array(0);
-
-    let velocity_buffer_b = Arc::new((gl.create_buffer().unwrap()));
-    gl.bind_buffer(GL::ARRAY_BUFFER, Some(&velocity_buffer_b));
-    gl.buffer_data_with_array_buffer_view(GL::ARRAY_BUFFER, &velocity_data_js, GL::STREAM_COPY);
-    gl.vertex_attrib_pointer_with_i32(1, 3, GL::FLOAT, false, 0, 0);
-    gl.enable_vertex_attrib_array(1);
-
-    gl.bind_buffer(GL::ARRAY_BUFFER, Some(&color_buffer));
-    gl.vertex_attrib_pointer_with_i32(2, 3, GL::FLOAT, false, 0, 0);
-    gl.enable_vertex_attrib_array(2);
-
-    gl.bind_buffer(GL::ARRAY_BUFFER, None);
-
-    let transform_feedback_b = Arc::new(gl.create_transform_feedback().unwrap());
-    gl.bind_transform_feedback(GL::TRANSFORM_FEEDBACK, Some(&transform_feedback_b));
-    gl.bind_buffer_base(GL::TRANSFORM_FEEDBACK_BUFFER, 0, Some(&position_buffer_b));
-    gl.bind_buffer_base(GL::TRANSFORM_FEEDBACK_BUFFER, 1, Some(&velocity_buffer_b));
-
-    gl.bind_vertex_array(None);
-    gl.bind_transform_feedback(GL::TRANSFORM_FEEDBACK, None);
-
-    let mut current_vertex_array : Arc<Mutex<_>> = Arc::new(Mutex::new((*vertex_array_a).clone()));
-    let mut current_transform_feedback : Arc<Mutex<_>> = Arc::new(Mutex::new((*transform_feedback_b).clone()));
-
-    let mut mass_uniform_data : [f32; 20] = [0.0; 20];
-
-    let localized_scale = 0.5;
-    let correction = localized_scale / 2.0;
-
-    mass_uniform_data[0] = (js_sys::Math::random() / ((NUM_PARTICLES as f64) * 1.5)) as f32;
-    mass_uniform_data[1] = (js_sys::Math::random() / ((NUM_PARTICLES as f64) * 1.5)) as f32;
-    mass_uniform_data[2] = (js_sys::Math::random() / (NUM_PARTICLES as f64) * 1.5) as f32;
-
-    mass_uniform_data[4] = ((((js_sys::Math::random() as f32) * LOCALIZED_SCALE) as f32) - CORRECTION) as f32;
-    mass_uniform_data[5] = ((((js_sys::Math::random() as f32) * LOCALIZED_SCALE) as f32) - CORRECTION) as f32;
-    mass_uniform_data[6] = ((((js_sys::Math::random() as f32) * LOCALIZED_SCALE) as f32) - CORRECTION) as f32;
-
-    mass_uniform_data[8] = ((((js_sys::Math::random() as f32) * LOCALIZED_SCALE) as f32) - CORRECTION) as f32;
-    mass_uniform_data[9] = ((((js_sys::Math::random() as f32) * LOCALIZED_SCALE) as f32) - CORRECTION) as f32;
-    mass_uniform_data[10] = ((((js_sys::Math::random() as f32) * LOCALIZED_SCALE) as f32) - CORRECTION) as f32;
-
-    mass_uniform_data[16] = ((((js_sys::Math::random() as f32) * LOCALIZED_SCALE) as f32) - CORRECTION) as f32;
-    mass_uniform_data[17] = ((((js_sys::Math::random() as f32) * LOCALIZED_SCALE) as f32) - CORRECTION) as f32;
-    mass_uniform_data[18] = ((((js_sys::Math::random() as f32) * LOCALIZED_SCALE) as f32) - CORRECTION) as f32;
-
-    let mass_uniform_buffer = gl.create_buffer();
-    gl.bind_buffer_base(GL::UNIFORM_BUFFER, 0, mass_uniform_buffer.as_ref());
-    let mass_uniform_data_js = js_sys::Float32Array::from(mass_uniform_data.as_slice());
-    
-    gl.buffer_data_with_array_buffer_view(GL::UNIFORM_BUFFER, &mass_uniform_data_js, GL::STATIC_DRAW);
-
-    let mut switch = Arc::new(Mutex::new(AtomicBool::new(true)));
 
     let game_state = create_game_state().unwrap();
 
-    set_player_one_events(game_state.clone());
-    set_player_two_events(game_state.clone());
+    let listeners = vec![
+        set_player_one_events(game_state.clone()),
+        set_player_two_events(game_state.clone()),
+    ];
 
     // let game_state = game_state.clone();
     let mut cursor = game_state.lock().unwrap().start_time.elapsed().as_millis();
@@ -299,9 +220,7 @@ fn render_game
 
     let bare_shader_program = setup_bare_test_shaders(gl.clone()).unwrap();
 
-    let render_loop_closure = Rc::new(RefCell::new(None));
-    let alias_rlc = render_loop_closure.clone();
-    *alias_rlc.borrow_mut() = Some(Closure::wrap(Box::new(move || {
+    let frames = AnimationLoop::start(move |_| {
         let now = game_state.lock().unwrap().start_time.elapsed().as_millis();
         let time_delta = now - cursor;
         cursor = now;
@@ -310,62 +229,20 @@ fn render_game
         gl.clear(GL::COLOR_BUFFER_BIT);
 
         let game_state = update_game_state(time_delta, game_state.clone()).unwrap();
-        draw_players(
-            gl.clone(),
-            game_state.clone(),
-            player_vertex_buffer.clone(),
-            player_js_vertices.clone(),
-            player_shader_program.clone(),
-            player_vertices_position.clone(),
-            time_location.clone(),
-            player_pos_deltas_loc.clone(),
-            player_vifo_theta_loc.clone(),
-        );
-
-        draw_torps(
-            gl.clone(),
-            game_state.clone(),
-            torp_vertex_buffer.clone(),
-            torp_js_vertices.clone(),
-            torp_shader_program.clone(),
-            torp_vertices_position.clone(),
-            time_location_2.clone(),
-            torp_pos_deltas_loc.clone(),
-            torp_vifo_theta_loc.clone(),
-        );
+        draw_players(&gl, &game_state, &player_shape);
+
+        draw_torps(&gl, &game_state, &torp_shape);
 
         // draw_bare_test(
         //     gl.clone(),
         //     bare_shader_program.clone(),
         // );
 
-        draw_particles(
-            gl.clone(),
-            particles_shader_program.clone(),
-            vertex_array_a.clone(),
-            vertex_array_b.clone(),
-            current_vertex_array.clone(),
-            current_transform_feedback.clone(),
-            transform_feedback_a.clone(),
-            transform_feedback_b.clone(),
-            position_buffer_a.clone(),
-            velocity_buffer_a.clone(),
-            position_buffer_b.clone(),
-            velocity_buffer_b.clone(),
-            switch.clone(),
-        );
-
-        request_animation_frame(render_loop_closure.borrow().as_ref().unwrap());
-    }) as Box<dyn FnMut()>));
-
-    request_animation_frame(alias_rlc.borrow().as_ref().unwrap());
+        draw_particles(&gl, &particles_shader_program, &particle_system);
+    });
 
-}
+    GameSession::new(frames, listeners)
 
-fn request_animation_frame(f: &Closure<dyn FnMut()>) {
-    window().unwrap()
-        .request_animation_frame(f.as_ref().unchecked_ref())
-        .expect("should register `requestAnimationFrame` OK");
 }
 
 fn setup_bare_test_shaders
@@ -375,21 +252,9 @@ fn setup_bare_test_shaders
 )
 -> Result<Arc<web_sys::WebGlProgram>, &'a str>
 {
-    let vert_code = include_str!("../shaders/bare.vert");
-    let frag_code = include_str!("../shaders/bare.frag");
-    let vert_shader = gl.create_shader(GL::VERTEX_SHADER).unwrap();
-    let frag_shader = gl.create_shader(GL::FRAGMENT_SHADER).unwrap();
-    gl.shader_source(&vert_shader, vert_code);
-    gl.shader_source(&frag_shader, frag_code);
-    let vert_log = gl.get_shader_info_log(&vert_shader);
-    let frag_log = gl.get_shader_info_log(&frag_shader);
-    log!("vert log", vert_log);
-    log!("frag log", frag_log);
-
-    let shader_program = gl.create_program().unwrap();
-    gl.attach_shader(&shader_program, &vert_shader);
-    gl.attach_shader(&shader_program, &frag_shader);
-    gl.link_program(&shader_program);
+    let shader_program = ProgramBuilder::new(&gl, BARE_VERT, BARE_FRAG)
+        .build()
+        .map_err(|e| { report(&e); "bare shader program failed to build" })?;
 
     Ok(Arc::new(shader_program))
 }
@@ -406,75 +271,32 @@ fn draw_bare_test
 
 fn draw_particles
 (
-    gl: Arc<GL>,
-    shader_program: Arc<web_sys::WebGlProgram>,
-    vertex_array_a: Arc<web_sys::WebGlVertexArrayObject>,
-    vertex_array_b: Arc<web_sys::WebGlVertexArrayObject>,
-    mut current_vertex_array: Arc<Mutex<web_sys::WebGlVertexArrayObject>>,
-    mut current_transform_feedback: Arc<Mutex<web_sys::WebGlTransformFeedback>>,
-    transform_feedback_a: Arc<web_sys::WebGlTransformFeedback>,
-    transform_feedback_b: Arc<web_sys::WebGlTransformFeedback>,
-    
-    position_buffer_a: Arc<web_sys::WebGlBuffer>,
-    velocity_buffer_a: Arc<web_sys::WebGlBuffer>,
-    position_buffer_b: Arc<web_sys::WebGlBuffer>,
-    velocity_buffer_b: Arc<web_sys::WebGlBuffer>,
-    switch: Arc<Mutex<AtomicBool>>,
-)   
+    gl: &GL,
+    shader_program: &web_sys::WebGlProgram,
+    particle_system: &GpuParticleSystem,
+)
 {
-    gl.use_program(Some(&shader_program));
-    gl.bind_vertex_array(Some(current_vertex_array.lock().unwrap().as_ref()));
-    gl.bind_transform_feedback(GL::TRANSFORM_FEEDBACK, Some(current_transform_feedback.lock().unwrap().as_ref()));
-
-    let s = *switch.lock().unwrap().get_mut();
-
-
-    // if *current_transform_feedback.lock().unwrap() == *transform_feedback_a.lock().unwrap() {
-    if !s {
-        // log!("tic");
-        gl.bind_buffer_base(GL::TRANSFORM_FEEDBACK_BUFFER, 0, Some(&position_buffer_a));
-        gl.bind_buffer_base(GL::TRANSFORM_FEEDBACK_BUFFER, 1, Some(&velocity_buffer_a));
-    } else {
-        // log!("tac");
-        gl.bind_buffer_base(GL::TRANSFORM_FEEDBACK_BUFFER, 0, Some(&position_buffer_b));
-        gl.bind_buffer_base(GL::TRANSFORM_FEEDBACK_BUFFER, 1, Some(&velocity_buffer_b));
-    }
-
-    gl.begin_transform_feedback(GL::POINTS);
-    gl.draw_arrays(GL::POINTS, 0, NUM_PARTICLES as i32);
-    gl.end_transform_feedback();
-
-    if s {
-        // log!("a");
-        *current_vertex_array.lock().unwrap() = (*vertex_array_b).clone();
-        *current_transform_feedback.lock().unwrap() = (*transform_feedback_a).clone();
-    } else 
-    {
-        // log!("b");
-        *current_vertex_array.lock().unwrap() = (*vertex_array_a).clone();
-        *current_transform_feedback.lock().unwrap() = (*transform_feedback_b).clone();
-    }
-
-    *switch.lock().unwrap().get_mut() = !s; 
-
-    gl.bind_vertex_array(None);
+    gl.use_program(Some(shader_program));
+    particle_system.step(gl);
+}
 
+// A mesh and the program that draws it, with where its inputs go.
+struct Shape {
+    vertex_buffer: WebGlBuffer,
+    js_vertices: js_sys::Float32Array,
+    shader_program: WebGlProgram,
+    vertices_position: u32,
+    pos_deltas_loc: WebGlUniformLocation,
+    vifo_theta_loc: WebGlUniformLocation,
+    time_location: WebGlUniformLocation,
 }
 
 fn setup_torp_shaders
 <'a>
 (
-    gl: Arc<GL>,
+    gl: &GL,
 )
--> Result<(
-    Arc<WebGlBuffer>, // torp vertex buffer
-    Arc<js_sys::Float32Array>, // torp_js_vertices,
-    Arc<web_sys::WebGlProgram>, // torp_shader_program,
-    Arc<u32>, // torp_vertices_position
-    Arc<WebGlUniformLocation>, //torp_pos_deltas_loc
-    Arc<WebGlUniformLocation>, // torp_vifo_theta_loc
-    Arc::<WebGlUniformLocation>, // time_location
-), &'a str>
+-> Result<Shape, &'a str>
 {
     let torpedo_100_vertices: Vec<f32> = vec![
         0.007, 0.0,
@@ -482,23 +304,9 @@ fn setup_torp_shaders
         -0.0038, 0.0038, 
     ];
 
-    let torpedo_100_vert_code = include_str!("../shaders/torpedo_100.vert");
-    let torpedo_100_vert_shader = gl.create_shader(GL::VERTEX_SHADER).unwrap();
-    gl.shader_source(&torpedo_100_vert_shader, torpedo_100_vert_code);
-    gl.compile_shader(&torpedo_100_vert_shader);
-    let torpedo_100_vert_shader_log = gl.get_shader_info_log(&torpedo_100_vert_shader);
-    log!("torpedo_100 shader log: ", torpedo_100_vert_shader_log);
-
-    let frag_code = include_str!("../shaders/basic.frag");
-    let frag_shader = gl.create_shader(GL::FRAGMENT_SHADER).unwrap();
-    gl.shader_source(&frag_shader, frag_code);
-    gl.compile_shader(&frag_shader);
-    let basic_frag_shader_log = gl.get_shader_info_log(&frag_shader);
-
-    let torp_shader_program = gl.create_program().unwrap();
-    gl.attach_shader(&torp_shader_program, &torpedo_100_vert_shader);
-    gl.attach_shader(&torp_shader_program, &frag_shader);
-    gl.link_program(&torp_shader_program);
+    let torp_shader_program = ProgramBuilder::new(gl, TORPEDO_100_VERT, BASIC_FRAG)
+        .build()
+        .map_err(|e| { report(&e); "torpedo shader program failed to build" })?;
 
     let time_location = gl.get_uniform_location(&torp_shader_program, "u_time");
 
@@ -508,21 +316,15 @@ fn setup_torp_shaders
     let torp_vifo_theta_loc =  gl.get_uniform_location(&torp_shader_program, "vifo_theta");
     let torp_vertices_position = gl.get_attrib_location(&torp_shader_program, "b_position") as u32;
 
-    let frag_code = include_str!("../shaders/basic.frag");
-    let frag_shader = gl.create_shader(GL::FRAGMENT_SHADER).unwrap();
-    gl.shader_source(&frag_shader, frag_code);
-    gl.compile_shader(&frag_shader);
-    let basic_frag_shader_log = gl.get_shader_info_log(&frag_shader);
-
-    Ok((
-        Arc::new(torp_vertex_buffer),
-        Arc::new(torp_js_vertices),
-        Arc::new(torp_shader_program),
-        Arc::new(torp_vertices_position),
-        Arc::new(torp_pos_deltas_loc.unwrap()),
-        Arc::new(torp_vifo_theta_loc.unwrap()),
-        Arc::new(time_location.unwrap()),
-    ))
+    Ok(Shape {
+        vertex_buffer: torp_vertex_buffer,
+        js_vertices: torp_js_vertices,
+        shader_program: torp_shader_program,
+        vertices_position: torp_vertices_position,
+        pos_deltas_loc: torp_pos_deltas_loc.unwrap(),
+        vifo_theta_loc: torp_vifo_theta_loc.unwrap(),
+        time_location: time_location.unwrap(),
+    })
 }
 
 fn setup_particle_shaders
@@ -534,52 +336,21 @@ fn setup_particle_shaders
     Arc<web_sys::WebGlProgram> // particles_shader_pragram
 , &'a str>
 {
-    let particles_vert_code = include_str!("../shaders/particles.vert");
-    let particles_vert_shader = gl.create_shader(GL::VERTEX_SHADER).unwrap();
-    gl.shader_source(&particles_vert_shader, particles_vert_code);
-    gl.compile_shader(&particles_vert_shader);
-    let particles_vert_shader_log = gl.get_shader_info_log(&particles_vert_shader);
-    log!("particles_vert_shader log: ", particles_vert_shader_log);
-
-    let particles_frag_code = include_str!("../shaders/particles.frag");
-    let particles_frag_shader = gl.create_shader(GL::FRAGMENT_SHADER).unwrap();
-    gl.shader_source(&particles_frag_shader, particles_frag_code);
-    gl.compile_shader(&particles_frag_shader);
-    let particles_frag_shader_log = gl.get_shader_info_log(&particles_vert_shader);
-    log!("particles_frag_shader log: ", particles_frag_shader_log);
-
-
-    let particles_shader_program = gl.create_program().unwrap();
-    gl.attach_shader(&particles_shader_program, &particles_vert_shader);
-    gl.attach_shader(&particles_shader_program, &particles_frag_shader);
-
-    let data = r#"
-        ["vPosition", "vVelocity"]
-        "#;
-    let v: Value = serde_json::from_str(data).unwrap();
-    let js_val = wasm_bindgen::JsValue::from_serde(&v).unwrap();
-    gl.transform_feedback_varyings(&particles_shader_program, &js_val, GL::SEPARATE_ATTRIBS);
-    
-    gl.link_program(&particles_shader_program);
+    let particles_shader_program = ProgramBuilder::new(&gl, PARTICLES_VERT, PARTICLES_FRAG)
+        .transform_feedback_varyings(&["vPosition", "vVelocity"], GL::INTERLEAVED_ATTRIBS)
+        .build()
+        .map_err(|e| { report(&e); "particles shader program failed to build" })?;
     gl.use_program(Some(&particles_shader_program));
-    Ok((Arc::new(particles_shader_program)))
+    Ok(Arc::new(particles_shader_program))
 }
 
 // todo: separate shader compilations 
 fn setup_shaders
 <'a>
 (
-    gl: Arc<GL>,
+    gl: &GL,
 )
--> Result<(
-    Arc<WebGlBuffer>, // player_vertex_buffer
-    Arc<js_sys::Float32Array>, // player_js_vertices,
-    Arc<web_sys::WebGlProgram>, // player_shader_program,
-    Arc<u32>, // player_vertices_position
-    Arc<WebGlUniformLocation>, //player_pos_deltas_loc
-    Arc<WebGlUniformLocation>, //player_vifo_theta_loc
-    Arc::<WebGlUniformLocation>, // time_location
-), &'a str>
+-> Result<Shape, &'a str>
 {
     let vehicle_100_vertices: Vec<f32> = vec![
         0.021, 0.0, 
@@ -587,23 +358,9 @@ fn setup_shaders
         -0.008, 0.008,
     ];
 
-    let vehicle_100_vert_code = include_str!("../shaders/vehicle_100.vert");
-    let vehicle_100_vert_shader = gl.create_shader(GL::VERTEX_SHADER).unwrap();
-    gl.shader_source(&vehicle_100_vert_shader, vehicle_100_vert_code);
-    gl.compile_shader(&vehicle_100_vert_shader);
-    let vehicle_100_vert_shader_log = gl.get_shader_info_log(&vehicle_100_vert_shader);
-    log!("vehicle_100 shader log: ", vehicle_100_vert_shader_log);
-    
-    let frag_code = include_str!("../shaders/basic.frag");
-    let frag_shader = gl.create_shader(GL::FRAGMENT_SHADER).unwrap();
-    gl.shader_source(&frag_shader, frag_code);
-    gl.compile_shader(&frag_shader);
-    let basic_frag_shader_log = gl.get_shader_info_log(&frag_shader);
-
-    let player_shader_program = gl.create_program().unwrap();
-    gl.attach_shader(&player_shader_program, &vehicle_100_vert_shader);
-    gl.attach_shader(&player_shader_program, &frag_shader);
-    gl.link_program(&player_shader_program);
+    let player_shader_program = ProgramBuilder::new(gl, VEHICLE_100_VERT, BASIC_FRAG)
+        .build()
+        .map_err(|e| { report(&e); "player shader program failed to build" })?;
     
     let time_location = gl.get_uniform_location(&player_shader_program, "u_time");
 
@@ -613,26 +370,24 @@ fn setup_shaders
     let player_vifo_theta_loc =  gl.get_uniform_location(&player_shader_program, "vifo_theta");
     let player_vertices_position = gl.get_attrib_location(&player_shader_program, "a_position") as u32;
     
-    Ok((
-        Arc::new(player_vertex_buffer),
-        Arc::new(player_js_vertices),
-        Arc::new(player_shader_program),
-        Arc::new(player_vertices_position),
-        Arc::new(player_pos_deltas_loc.unwrap()),
-        Arc::new(player_vifo_theta_loc.unwrap()),
-        Arc::new(time_location.unwrap()),
-    ))
+    Ok(Shape {
+        vertex_buffer: player_vertex_buffer,
+        js_vertices: player_js_vertices,
+        shader_program: player_shader_program,
+        vertices_position: player_vertices_position,
+        pos_deltas_loc: player_pos_deltas_loc.unwrap(),
+        vifo_theta_loc: player_vifo_theta_loc.unwrap(),
+        time_location: time_location.unwrap(),
+    })
 }
 
 fn set_player_two_events
-<'a>
 (
     game_state: Arc<Mutex<GameState>>,
 )
+-> EventListener
 {
-    let document = web_sys::window().unwrap().document().unwrap();
-    let et_keys : EventTarget = document.into();
-    let keypress_cb = Closure::wrap(Box::new(move |event: KeyboardEvent| {
+    EventListener::on_document("keydown", move |event: KeyboardEvent| {
         match event.key_code() {
     74 => game_state.lock().unwrap().player_two.lock().unwrap().vifo_theta -= Rad(0.1),
     79 => {
@@ -650,7 +405,7 @@ fn set_player_two_events
         // // assert vnvs_scalar == vnsv_scalar_2;
         game_state.lock().unwrap().player_two.lock().unwrap().velocity_dx = vnsv_dx;
         game_state.lock().unwrap().player_two.lock().unwrap().velocity_dy = vnsv_dy;
-        game_state.lock().unwrap().player_two.lock().unwrap().velocity_theta = vnsv_theta.into();
+        game_state.lock().unwrap().player_two.lock().unwrap().velocity_theta = vnsv_theta;
         game_state.lock().unwrap().player_two.lock().unwrap().velocity_scalar = vnsv_scalar;
     },
     186 => game_state.lock().unwrap().player_two.lock().unwrap().vifo_theta += Rad(0.1),
@@ -684,22 +439,16 @@ fn set_player_two_events
             _ => (),
         }
 
-    }) as Box<dyn FnMut(KeyboardEvent)>);
-    et_keys
-        .add_event_listener_with_callback("keydown", keypress_cb.as_ref().unchecked_ref())
-        .unwrap();
-    keypress_cb.forget();
+    })
 }
 
 fn set_player_one_events
-<'a>
 (
     game_state: Arc<Mutex<GameState>>,
 )
+-> EventListener
 {
-    let document = web_sys::window().unwrap().document().unwrap();
-    let et_keys : EventTarget = document.into();
-    let keypress_cb = Closure::wrap(Box::new(move |event: KeyboardEvent| {
+    EventListener::on_document("keydown", move |event: KeyboardEvent| {
         match event.key_code() {
             39 => game_state.lock().unwrap().player_one.lock().unwrap().vifo_theta -= Rad(0.1),
             38 => {
@@ -717,7 +466,7 @@ fn set_player_one_events
                 // // assert vnvs_scalar == vnsv_scalar_2;
                 game_state.lock().unwrap().player_one.lock().unwrap().velocity_dx = vnsv_dx;
                 game_state.lock().unwrap().player_one.lock().unwrap().velocity_dy = vnsv_dy;
-                game_state.lock().unwrap().player_one.lock().unwrap().velocity_theta = vnsv_theta.into();
+                game_state.lock().unwrap().player_one.lock().unwrap().velocity_theta = vnsv_theta;
                 game_state.lock().unwrap().player_one.lock().unwrap().velocity_scalar = vnsv_scalar;
             },
             37 => game_state.lock().unwrap().player_one.lock().unwrap().vifo_theta += Rad(0.1),
@@ -751,34 +500,24 @@ fn set_player_one_events
             _ => (),
         }
 
-    }) as Box<dyn FnMut(KeyboardEvent)>);
-    et_keys
-        .add_event_listener_with_callback("keydown", keypress_cb.as_ref().unchecked_ref())
-        .unwrap();
-    keypress_cb.forget();
+    })
 }
 
 fn draw_torps
 (
-    gl: Arc<GL>,
-    game_state: Arc<Mutex<GameState>>,
-    torp_vertex_buffer: Arc<WebGlBuffer>,
-    torp_js_vertices: Arc<js_sys::Float32Array>,
-    shader_program: Arc<web_sys::WebGlProgram>,
-    torp_vertices_position: Arc<u32>,
-    time_location: Arc<WebGlUniformLocation>,
-    torp_pos_deltas_loc: Arc<WebGlUniformLocation>,
-    torp_vifo_theta_loc: Arc<WebGlUniformLocation>,
+    gl: &GL,
+    game_state: &Mutex<GameState>,
+    shape: &Shape,
 )
 {
-    gl.use_program(Some(&shader_program));
-    gl.bind_buffer(GL::ARRAY_BUFFER, Some(&torp_vertex_buffer));
-    gl.buffer_data_with_array_buffer_view(GL::ARRAY_BUFFER, &torp_js_vertices, GL::STATIC_DRAW);
-    gl.vertex_attrib_pointer_with_i32(*torp_vertices_position, 2, GL::FLOAT, false, 0, 0);
-    gl.enable_vertex_attrib_array(*torp_vertices_position);
+    gl.use_program(Some(&shape.shader_program));
+    gl.bind_buffer(GL::ARRAY_BUFFER, Some(&shape.vertex_buffer));
+    gl.buffer_data_with_array_buffer_view(GL::ARRAY_BUFFER, &shape.js_vertices, GL::STATIC_DRAW);
+    gl.vertex_attrib_pointer_with_i32(shape.vertices_position, 2, GL::FLOAT, false, 0, 0);
+    gl.enable_vertex_attrib_array(shape.vertices_position);
 
-    // gl.use_program(Some(&shader_program));
-    gl.uniform1f(Some(&time_location), 0.4 as f32);
+    // gl.use_program(Some(&shape.shader_program));
+    gl.uniform1f(Some(&shape.time_location), 0.4);
 
     for (idx, torp) in game_state.lock().unwrap()
     .torps_in_flight.lock().unwrap()
@@ -786,53 +525,46 @@ fn draw_torps
         let new_pos_dx = torp.lock().unwrap().position_dx;
         let new_pos_dy = torp.lock().unwrap().position_dy;
         let torp_vifo_theta = torp.lock().unwrap().vifo_theta;
-        gl.uniform2f(Some(&torp_pos_deltas_loc), new_pos_dx, new_pos_dy);
-        gl.uniform1f(Some(&torp_vifo_theta_loc), torp_vifo_theta.0);
+        gl.uniform2f(Some(&shape.pos_deltas_loc), new_pos_dx, new_pos_dy);
+        gl.uniform1f(Some(&shape.vifo_theta_loc), torp_vifo_theta.0);
         gl.draw_arrays(GL::TRIANGLES, 0, 6);
     }
 }
 
 fn draw_players
-// <'a>
 (
-    gl: Arc<GL>,
-    game_state: Arc<Mutex<GameState>>,
-    player_vertex_buffer: Arc<WebGlBuffer>,
-    player_js_vertices: Arc<js_sys::Float32Array>,
-    shader_program: Arc<web_sys::WebGlProgram>,
-    player_vertices_position: Arc<u32>,
-    time_location: Arc<WebGlUniformLocation>,
-    player_pos_deltas_loc: Arc<WebGlUniformLocation>,
-    player_vifo_theta_loc: Arc<WebGlUniformLocation>,
+    gl: &GL,
+    game_state: &Mutex<GameState>,
+    shape: &Shape,
 )
 {
-    gl.use_program(Some(&shader_program));
-    gl.bind_buffer(GL::ARRAY_BUFFER, Some(&player_vertex_buffer));
-    gl.buffer_data_with_array_buffer_view(GL::ARRAY_BUFFER, &player_js_vertices, GL::STATIC_DRAW);
-    gl.vertex_attrib_pointer_with_i32(*player_vertices_position, 2, GL::FLOAT, false, 0, 0);
-    // gl.vertex_attrib_pointer_with_i32(*player_vertices_position, 2, GL::FLOAT, false, 0, 0);
-    gl.enable_vertex_attrib_array(*player_vertices_position);
+    gl.use_program(Some(&shape.shader_program));
+    gl.bind_buffer(GL::ARRAY_BUFFER, Some(&shape.vertex_buffer));
+    gl.buffer_data_with_array_buffer_view(GL::ARRAY_BUFFER, &shape.js_vertices, GL::STATIC_DRAW);
+    gl.vertex_attrib_pointer_with_i32(shape.vertices_position, 2, GL::FLOAT, false, 0, 0);
+    // gl.vertex_attrib_pointer_with_i32(shape.vertices_position, 2, GL::FLOAT, false, 0, 0);
+    gl.enable_vertex_attrib_array(shape.vertices_position);
 
     
-    gl.uniform1f(Some(&time_location), 0.4 as f32);
+    gl.uniform1f(Some(&shape.time_location), 0.4);
 
     let new_pos_dx = game_state.lock().unwrap().player_one.lock().unwrap().position_dx;
     let new_pos_dy = game_state.lock().unwrap().player_one.lock().unwrap().position_dy;
 
-    gl.uniform2f(Some(&player_pos_deltas_loc), new_pos_dx, new_pos_dy);
+    gl.uniform2f(Some(&shape.pos_deltas_loc), new_pos_dx, new_pos_dy);
     
     let new_vifo_theta = game_state.lock().unwrap().player_one.lock().unwrap().vifo_theta;
-    gl.uniform1f(Some(&player_vifo_theta_loc), new_vifo_theta.0);
+    gl.uniform1f(Some(&shape.vifo_theta_loc), new_vifo_theta.0);
     gl.draw_arrays(GL::TRIANGLES, 0, 6);
     // gl.bind_buffer(GL::ARRAY_BUFFER, None);
 
     // let new_pos_dx = game_state.lock().unwrap().player_two.lock().unwrap().position_dx;
     // let new_pos_dy = game_state.lock().unwrap().player_two.lock().unwrap().position_dy;
 
-    // gl.uniform2f(Some(&player_pos_deltas_loc), new_pos_dx, new_pos_dy);
+    // gl.uniform2f(Some(&shape.pos_deltas_loc), new_pos_dx, new_pos_dy);
     
     // let new_vifo_theta = game_state.lock().unwrap().player_two.lock().unwrap().vifo_theta;
-    // gl.uniform1f(Some(&player_vifo_theta_loc), new_vifo_theta.0);
+    // gl.uniform1f(Some(&shape.vifo_theta_loc), new_vifo_theta.0);
     // gl.draw_arrays(GL::TRIANGLES, 0, 6);
 }
 
@@ -849,16 +581,16 @@ fn update_game_state // A slight misnomer, as game state is also mutated by even
 
     let delta_scalar = (time_delta as f32) * 0.001;
     let old_pos_dx = game_state.lock().unwrap().player_one.lock().unwrap().position_dx;
-    let additional_dx = game_state.lock().unwrap().player_one.lock().unwrap().velocity_dx * (delta_scalar as f32);
+    let additional_dx = game_state.lock().unwrap().player_one.lock().unwrap().velocity_dx * delta_scalar;
     let mut new_pos_dx = old_pos_dx + additional_dx;
     if new_pos_dx < -1.0 {
-        new_pos_dx = new_pos_dx + 2.0;
+        new_pos_dx += 2.0;
     }
     if new_pos_dx > 1.0 {
-        new_pos_dx = new_pos_dx - 2.0;
+        new_pos_dx -= 2.0;
     }
     let old_pos_dy = game_state.lock().unwrap().player_one.lock().unwrap().position_dy;
-    let additional_dy = game_state.lock().unwrap().player_one.lock().unwrap().velocity_dy * (delta_scalar as f32);
+    let additional_dy = game_state.lock().unwrap().player_one.lock().unwrap().velocity_dy * delta_scalar;
     let mut new_pos_dy = old_pos_dy + additional_dy;
     if new_pos_dy < -1.0 {
         new_pos_dy += 2.0;
@@ -890,17 +622,17 @@ fn update_game_state // A slight misnomer, as game state is also mutated by even
     }    
     
     let old_pos_dx = game_state.lock().unwrap().player_two.lock().unwrap().position_dx;
-    let additional_dx = game_state.lock().unwrap().player_two.lock().unwrap().velocity_dx * (delta_scalar as f32);
+    let additional_dx = game_state.lock().unwrap().player_two.lock().unwrap().velocity_dx * delta_scalar;
     let mut new_pos_dx = old_pos_dx + additional_dx;
     if new_pos_dx < -1.0 {
-        new_pos_dx = new_pos_dx + 2.0;
+        new_pos_dx += 2.0;
     }
     if new_pos_dx > 1.0 {
-        new_pos_dx = new_pos_dx - 2.0;
+        new_pos_dx -= 2.0;
     }
 
     let old_pos_dy = game_state.lock().unwrap().player_two.lock().unwrap().position_dy;
-    let additional_dy = game_state.lock().unwrap().player_two.lock().unwrap().velocity_dy * (delta_scalar as f32);
+    let additional_dy = game_state.lock().unwrap().player_two.lock().unwrap().velocity_dy * delta_scalar;
     let mut new_pos_dy = old_pos_dy + additional_dy;
     if new_pos_dy < -1.0 {
         new_pos_dy += 2.0;
@@ -936,7 +668,7 @@ fn update_game_state // A slight misnomer, as game state is also mutated by even
 
     let mut v : Vec<Arc<Mutex<Vehicle_100>>> = vec![];
     for (idx, torp) in torps_in_flight.lock().unwrap().iter().enumerate() {
-        if !((new_pos_dx < -1.0) || (new_pos_dx > 1.0) || (new_pos_dy < -1.0) || (new_pos_dy > 1.0)) {
+        if (-1.0..=1.0).contains(&new_pos_dx) && (-1.0..=1.0).contains(&new_pos_dy) {
             v.push(Arc::new(Mutex::new(*torp.lock().unwrap())));
         } 
     }
@@ -978,13 +710,13 @@ fn update_game_state // A slight misnomer, as game state is also mutated by even
     }
 
     for (key, space) in collisions_map.iter() {
-        if (space.0 == true) && (space.2.len() > 0) {
+        if space.0 && !space.2.is_empty() {
             log!("Torpedo kills player one.");
         }
-        if (space.0 == true) && (space.1 == true) {
+        if space.0 && space.1 {
             log!("vehicle collision");
         }
-        if (space.1 == true) && (space.2.len() > 0) {
+        if space.1 && !space.2.is_empty() {
             log!("Torpedo kills player 2.");
         }
 
@@ -1024,17 +756,14 @@ fn create_game_state
     }));
 
     let torps_in_flight = Arc::new(Mutex::new(vec![]));
-    let collisions_two : Arc<Mutex<
-        HashMap<&'static str, Arc<Mutex<CollisionSpace>>>
-    >> = 
-        Arc::new(Mutex::new(HashMap::new()));
+    let collisions_two : CollisionsMap = Arc::new(Mutex::new(HashMap::new()));
 
     // let collisions : Arc<Mutex<Vec<_>>> = Arc::new(Mutex::new(vec![]));
 
     let game_state = GameState {
-        player_one: player_one,
-        player_two: player_two,
-        torps_in_flight: torps_in_flight,
+        player_one,
+        player_two,
+        torps_in_flight,
         start_time: Arc::new(Instant::now()),
         elapsed_time: Arc::new(Mutex::new(0)),
         game_over: Arc::new(Mutex::new(false)),
@@ -1049,6 +778,7 @@ fn create_game_state
 
 // type CollisionSpace = (bool, bool, Arc<Mutex<Vec<usize>>>);
 type CollisionSpace = (bool, bool, Vec<usize>);
+type CollisionsMap = Arc<Mutex<HashMap<&'static str, Arc<Mutex<CollisionSpace>>>>>;
 
 
 
@@ -1060,13 +790,14 @@ struct GameState
     start_time: Arc<Instant>,
     elapsed_time: Arc<Mutex<u128>>,
     game_over: Arc<Mutex<bool>>,
-    collisions_map: Arc<Mutex<HashMap<&'static str, Arc<Mutex<CollisionSpace>>>>>,
+    collisions_map: CollisionsMap,
     // model an explosion around a vector sum of the collided vehicles, with extra effects. covering torpedo collisions
     // This would be a good place to use Rust traits.
     result: Arc<Mutex<u8>>,
     mode: Arc<u8>, // 1 player vs computer, 2 player local, 2 player network
 }
 
+#[allow(non_camel_case_types)]
 #[derive(Copy, Clone)]
 struct Vehicle_100 {
     position_dx: f32, // raw displacement in x, y
@@ -0,0 +1,337 @@
+// The game, in whichever mode the menu picked.  On its first render the host starts one
+// `GameSession`: the keyboard listeners and the loop that runs the simulation at a fixed
+// tick, records every tick for the replay, and draws.  `destroy` drops the session, so
+// leaving the route stops it all.
+//
+// Esc or a gamepad's Start pauses, and so does the page going out of sight or losing focus.  A paused loop
+// neither ticks nor counts the time, so play picks up where it stopped rather than
+// catching up on everything it missed.
+
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+
+use gloo_console::log;
+use wasm_bindgen::JsCast;
+use web_sys::{
+    window, Event, Gamepad, GamepadButton, GamepadMappingType, HtmlCanvasElement, KeyboardEvent, MouseEvent,
+};
+use yew::{html, Callback, Component, Context, Html, NodeRef, Properties};
+use yew_router::prelude::*;
+
+use crate::components::host::{AnimationLoop, EventListener, GameSession};
+use crate::components::hud::{Hud, HudOverlay};
+use crate::components::renderer_fallback::RendererFallback;
+use crate::renderer::{
+    RenderFrame, Renderer, RendererError, ARENA_OVERLAY_STYLE, CANVAS_BOX_STYLE, CANVAS_STYLE,
+};
+use crate::replay::browser::download_replay;
+use crate::replay::ReplayRecorder;
+use crate::settings::Settings;
+use crate::simulation::{
+    computer_controls, update_game_state, GameMode, GameState, MatchConfig, TickInput, PLAYER_TWO, TICK_MILLIS,
+};
+use crate::Route;
+
+const ESCAPE: u32 = 27;
+// Start, in the browser's standard gamepad layout
+const GAMEPAD_START: u32 = 9;
+// The most time one frame may owe the simulation.  A frame that took longer (a GC pause,
+// a stalled tab, the debugger) plays on slowed down rather than running a burst of ticks.
+const MAX_FRAME_MILLIS: f64 = TICK_MILLIS as f64 * 8.0;
+
+const PAUSED_CANVAS_STYLE: &str = "filter: brightness(0.35);";
+const PAUSE_MENU_STYLE: &str = "display: flex; flex-direction: column; align-items: center; \
+    justify-content: center; gap: 1.5cqw; pointer-events: auto; font-family: monospace; \
+    color: #e8e8f0;";
+const PAUSE_TITLE_STYLE: &str = "font-size: 5cqw; letter-spacing: 0.2em; margin-bottom: 2cqw;";
+const PAUSE_BUTTON_STYLE: &str = "font: inherit; font-size: 2.6cqw; width: 18em; padding: 0.4em;";
+
+pub enum Msg {
+    Hud(Hud),
+    RendererFailed(RendererError),
+    TogglePause,
+    Pause,
+    Resume,
+    Restart,
+    Quit,
+}
+
+// The match being played: its state and everything recorded of it so far.
+struct Game {
+    state: GameState,
+    recorder: ReplayRecorder,
+}
+
+impl Game {
+    fn new(config: MatchConfig) -> Self {
+        let seed = (js_sys::Math::random() * (u32::MAX as f64)) as u64;
+        let recorder = ReplayRecorder::new(seed, config);
+        Self { state: recorder.replay().initial_state(), recorder }
+    }
+}
+
+#[derive(Properties, PartialEq)]
+pub struct GameHostProps {
+    pub config: MatchConfig,
+    // keys, graphics and names, as they were when the game started
+    pub settings: Rc<Settings>,
+}
+
+pub struct GameHost {
+    canvas: NodeRef,
+    game: Rc<RefCell<Game>>,
+    paused: Rc<Cell<bool>>,
+    hud: Hud,
+    renderer_error: Option<RendererError>,
+    session: Option<GameSession>,
+}
+
+impl Component for GameHost {
+    type Message = Msg;
+    type Properties = GameHostProps;
+
+    fn create(ctx: &Context<Self>) -> Self {
+        let game = Game::new(ctx.props().config.clone());
+        Self {
+            canvas: NodeRef::default(),
+            hud: Hud::from_game_state(&game.state),
+            game: Rc::new(RefCell::new(game)),
+            paused: Rc::new(Cell::new(false)),
+            renderer_error: None,
+            session: None,
+        }
+    }
+
+    fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
+        match msg {
+            Msg::Hud(hud) => {
+                self.hud = hud;
+                true
+            }
+            Msg::RendererFailed(e) => {
+                self.session = None;
+                self.renderer_error = Some(e);
+                true
+            }
+            Msg::TogglePause => {
+                self.paused.set(!self.paused.get());
+                true
+            }
+            // the page going away only ever pauses; coming back waits for the players
+            Msg::Pause => !self.paused.replace(true),
+            Msg::Resume => {
+                self.paused.set(false);
+                true
+            }
+            Msg::Restart => {
+                self.restart(ctx);
+                true
+            }
+            Msg::Quit => {
+                if let Some(history) = ctx.link().history() {
+                    history.push(Route::Menu);
+                }
+                false
+            }
+        }
+    }
+
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        if let Some(e) = &self.renderer_error {
+            return html! { <RendererFallback error={e.clone()} /> };
+        }
+        let settings = &ctx.props().settings;
+        let game = self.game.clone();
+        let download = Callback::from(move |_: MouseEvent| {
+            let game = game.borrow();
+            let replay = game.recorder.replay();
+            if let Err(e) = download_replay(replay, &format!("spacewar-{}", replay.seed)) {
+                log!("replay download failed", e);
+            }
+        });
+        let canvas_style = match self.paused.get() {
+            true => format!("{} {}", CANVAS_STYLE, PAUSED_CANVAS_STYLE),
+            false => String::from(CANVAS_STYLE),
+        };
+        html! {
+            <div>
+                <div style={CANVAS_BOX_STYLE}>
+                    <canvas style={canvas_style} ref={self.canvas.clone()} />
+                    <HudOverlay hud={self.hud.clone()} names={settings.names.clone()} palette={settings.palette} />
+                    { self.view_pause_menu(ctx) }
+                </div>
+                <button onclick={download}>{"Download replay"}</button>
+            </div>
+        }
+    }
+
+    // another mode from the menu: a fresh match on the same canvas
+    fn changed(&mut self, ctx: &Context<Self>) -> bool {
+        if self.game.borrow().state.config != ctx.props().config {
+            self.restart(ctx);
+        }
+        true
+    }
+
+    fn rendered(&mut self, ctx: &Context<Self>, first_render: bool) {
+        // the HUD re-renders this component, but there is only ever one session
+        if !first_render {
+            return;
+        }
+        let canvas = match self.canvas.cast::<HtmlCanvasElement>() {
+            Some(canvas) => canvas,
+            None => return,
+        };
+        match start_session(ctx, &canvas, self.game.clone(), self.paused.clone()) {
+            Ok(session) => self.session = Some(session),
+            Err(e) => ctx.link().send_message(Msg::RendererFailed(e)),
+        }
+    }
+
+    fn destroy(&mut self, _ctx: &Context<Self>) {
+        self.session = None;
+    }
+}
+
+impl GameHost {
+    fn restart(&mut self, ctx: &Context<Self>) {
+        let game = Game::new(ctx.props().config.clone());
+        self.hud = Hud::from_game_state(&game.state);
+        *self.game.borrow_mut() = game;
+        self.paused.set(false);
+    }
+
+    fn view_pause_menu(&self, ctx: &Context<Self>) -> Html {
+        if !self.paused.get() {
+            return html! {};
+        }
+        let link = ctx.link();
+        html! {
+            <div style={format!("{} {}", ARENA_OVERLAY_STYLE, PAUSE_MENU_STYLE)}>
+                <div style={PAUSE_TITLE_STYLE}>{"PAUSED"}</div>
+                <button style={PAUSE_BUTTON_STYLE} onclick={link.callback(|_| Msg::Resume)}>{"Resume"}</button>
+                <button style={PAUSE_BUTTON_STYLE} onclick={link.callback(|_| Msg::Restart)}>{"Restart"}</button>
+                <button style={PAUSE_BUTTON_STYLE} onclick={link.callback(|_| Msg::Quit)}>{"Quit to menu"}</button>
+            </div>
+        }
+    }
+}
+
+fn start_session(
+    ctx: &Context<GameHost>,
+    canvas: &HtmlCanvasElement,
+    game: Rc<RefCell<Game>>,
+    paused: Rc<Cell<bool>>,
+) -> Result<GameSession, RendererError> {
+    let mut renderer = Renderer::from_canvas(canvas)?;
+    let link = ctx.link();
+    let settings = ctx.props().settings.clone();
+    // key code, player, control
+    let keys: Vec<(u32, usize, u8)> = settings
+        .keys
+        .iter()
+        .enumerate()
+        .flat_map(|(player, keys)| keys.controls().map(|(code, control)| (code, player, control)))
+        .collect();
+
+    // Key handlers only note what was pressed; the loop hands it to the simulation on the
+    // next tick, and records it.
+    let pending_input = Rc::new(RefCell::new(TickInput::default()));
+    let key_presses = {
+        let pending_input = pending_input.clone();
+        let toggle_pause = link.callback(|_| Msg::TogglePause);
+        EventListener::on_document("keydown", move |event: KeyboardEvent| {
+            let key_code = event.key_code();
+            if key_code == ESCAPE {
+                return toggle_pause.emit(());
+            }
+            for (_, player, control) in keys.iter().filter(|(code, _, _)| *code == key_code) {
+                pending_input.borrow_mut().players[*player].press(*control);
+            }
+        })
+    };
+    let hidden = {
+        let pause = link.callback(|_| Msg::Pause);
+        EventListener::on_document("visibilitychange", move |_: Event| {
+            if window().and_then(|w| w.document()).is_some_and(|d| d.hidden()) {
+                pause.emit(());
+            }
+        })
+    };
+    let blurred = {
+        let pause = link.callback(|_| Msg::Pause);
+        EventListener::new(&window().expect("no window").into(), "blur", move |_: Event| pause.emit(()))
+    };
+
+    let on_hud = link.callback(Msg::Hud);
+    let mut last_timestamp: Option<f64> = None;
+    let mut lag = 0.0;
+    let mut last_hud = Hud::from_game_state(&game.borrow().state);
+    let toggle_pause = link.callback(|_| Msg::TogglePause);
+    let mut start_held = false;
+    let frames = AnimationLoop::start(move |timestamp| {
+        // Gamepads raise no events for their buttons, so Start is polled, paused or not, and
+        // toggles the pause once per press like Esc.
+        let start = gamepad_start_pressed();
+        if start && !start_held {
+            toggle_pause.emit(());
+        }
+        start_held = start;
+
+        // Hold the game still while it's paused or the WebGL context is lost, and forget
+        // the time: none of it is owed when play picks up again.
+        if paused.get() || !renderer.ready() {
+            last_timestamp = None;
+            pending_input.take();
+            return;
+        }
+        let elapsed = timestamp - last_timestamp.unwrap_or(timestamp);
+        last_timestamp = Some(timestamp);
+        lag += elapsed.min(MAX_FRAME_MILLIS);
+
+        let mut game = game.borrow_mut();
+        let Game { state, recorder } = &mut *game;
+        let mut events = vec![];
+        while lag >= TICK_MILLIS as f64 {
+            let mut input = pending_input.take();
+            if state.config.mode == GameMode::VersusComputer {
+                input.players[PLAYER_TWO] = computer_controls(state, PLAYER_TWO);
+            }
+            update_game_state(state, &input);
+            events.extend_from_slice(&state.events);
+            recorder.record(input, state);
+            lag -= TICK_MILLIS as f64;
+        }
+
+        let mut frame = RenderFrame::from_game_state(state, &events, elapsed as f32 * 0.001);
+        frame.star_chart = settings.graphics.star_chart;
+        frame.post = settings.graphics.post;
+        frame.palette = settings.palette;
+        frame.scale_particles(settings.graphics.particles);
+        renderer.draw(&frame);
+
+        let hud = Hud::from_game_state(state);
+        drop(game);
+        // outside the borrow: the component reads the game while re-rendering
+        if hud != last_hud {
+            last_hud = hud.clone();
+            on_hud.emit(hud);
+        }
+    });
+
+    Ok(GameSession::new(frames, vec![key_presses, hidden, blurred]))
+}
+
+// Whether Start is down on any connected gamepad the browser knows the layout of.
+fn gamepad_start_pressed() -> bool {
+    let gamepads = match window().map(|w| w.navigator().get_gamepads()) {
+        Some(Ok(gamepads)) => gamepads,
+        _ => return false,
+    };
+    gamepads
+        .iter()
+        .filter_map(|gamepad| gamepad.dyn_into::<Gamepad>().ok())
+        .filter(|gamepad| gamepad.mapping() == GamepadMappingType::Standard)
+        .filter_map(|gamepad| gamepad.buttons().get(GAMEPAD_START).dyn_into::<GamepadButton>().ok())
+        .any(|button| button.pressed())
+}
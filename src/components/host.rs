@@ -0,0 +1,112 @@
+// The browser side of a running game: its requestAnimationFrame loop and the listeners
+// it needs, owned rather than forgotten.  Dropping an `AnimationLoop` cancels the pending
+// frame and frees the closure; dropping an `EventListener` removes it.  Components start a
+// `GameSession` on their first render and drop it in `destroy`, so a route can be mounted
+// and unmounted any number of times without leaving a loop or a keydown handler behind.
+
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+use wasm_bindgen_futures::spawn_local;
+use web_sys::{window, Event, EventTarget};
+
+type FrameCallback = Closure<dyn FnMut(f64)>;
+
+pub struct AnimationLoop {
+    callback: Rc<RefCell<Option<FrameCallback>>>,
+    // the frame asked for and not yet run
+    pending: Rc<Cell<Option<i32>>>,
+}
+
+impl AnimationLoop {
+    // Calls `step` once a frame with the frame's timestamp in milliseconds, until dropped.
+    pub fn start(mut step: impl FnMut(f64) + 'static) -> Self {
+        let callback: Rc<RefCell<Option<FrameCallback>>> = Rc::new(RefCell::new(None));
+        let pending = Rc::new(Cell::new(None));
+
+        // weak, or the closure would keep itself alive
+        let this = Rc::downgrade(&callback);
+        let next = pending.clone();
+        *callback.borrow_mut() = Some(Closure::wrap(Box::new(move |timestamp: f64| {
+            next.set(None);
+            step(timestamp);
+            if let Some(callback) = this.upgrade() {
+                next.set(request_animation_frame(callback.borrow().as_ref()));
+            }
+        }) as Box<dyn FnMut(f64)>));
+
+        pending.set(request_animation_frame(callback.borrow().as_ref()));
+        Self { callback, pending }
+    }
+}
+
+impl Drop for AnimationLoop {
+    fn drop(&mut self) {
+        if let (Some(handle), Some(window)) = (self.pending.take(), window()) {
+            let _ = window.cancel_animation_frame(handle);
+        }
+        // Freed on the next microtask rather than here: when a frame's own step is what
+        // drops the loop, the closure is still running.
+        if let Some(callback) = self.callback.borrow_mut().take() {
+            spawn_local(async move { drop(callback) });
+        }
+    }
+}
+
+fn request_animation_frame(callback: Option<&FrameCallback>) -> Option<i32> {
+    let callback = callback?;
+    window()?.request_animation_frame(callback.as_ref().unchecked_ref()).ok()
+}
+
+pub struct EventListener {
+    target: EventTarget,
+    event_type: &'static str,
+    callback: Closure<dyn FnMut(Event)>,
+}
+
+impl EventListener {
+    // Calls `handler` with every `event_type` event on `target` that is an `E`, until
+    // dropped.
+    pub fn new<E: JsCast + 'static>(
+        target: &EventTarget,
+        event_type: &'static str,
+        mut handler: impl FnMut(E) + 'static,
+    ) -> Self {
+        let callback = Closure::wrap(Box::new(move |event: Event| {
+            if let Ok(event) = event.dyn_into::<E>() {
+                handler(event);
+            }
+        }) as Box<dyn FnMut(Event)>);
+        // only fails for a callback that isn't a function
+        let _ = target.add_event_listener_with_callback(event_type, callback.as_ref().unchecked_ref());
+        Self { target: target.clone(), event_type, callback }
+    }
+
+    // On the document, where key presses land whatever has focus.
+    pub fn on_document<E: JsCast + 'static>(event_type: &'static str, handler: impl FnMut(E) + 'static) -> Self {
+        let document: EventTarget = window().and_then(|w| w.document()).expect("no document").into();
+        Self::new(&document, event_type, handler)
+    }
+}
+
+impl Drop for EventListener {
+    fn drop(&mut self) {
+        let _ = self
+            .target
+            .remove_event_listener_with_callback(self.event_type, self.callback.as_ref().unchecked_ref());
+    }
+}
+
+// Everything a running game holds on the page.
+pub struct GameSession {
+    _frames: AnimationLoop,
+    _listeners: Vec<EventListener>,
+}
+
+impl GameSession {
+    pub fn new(frames: AnimationLoop, listeners: Vec<EventListener>) -> Self {
+        Self { _frames: frames, _listeners: listeners }
+    }
+}
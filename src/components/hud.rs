@@ -0,0 +1,257 @@
+// Scores, supplies and the round clock, laid over the arena as plain HTML.  Whoever runs
+// the render loop builds a `Hud` from the game state each frame and re-renders the
+// overlay only when it differs from the last one, which is a few times a second at most.
+
+use yew::prelude::*;
+
+use crate::renderer::{Palette, ARENA_OVERLAY_STYLE};
+use crate::settings::default_names;
+use crate::simulation::{
+    GameState, MatchResult, RoundPhase, PLAYER_ONE, PLAYER_TWO, TICK_MILLIS,
+};
+
+const HUD_STYLE: &str = "font-family: monospace; font-size: 2.6cqw; color: #e8e8f0; \
+    text-shadow: 0 0 0.3em #000;";
+const PLAYER_STYLES: [&str; 2] = [
+    "position: absolute; top: 1.5cqw; left: 2cqw;",
+    "position: absolute; top: 1.5cqw; right: 2cqw; text-align: right;",
+];
+const CLOCK_STYLE: &str = "position: absolute; top: 1.5cqw; width: 100%; text-align: center;";
+const BANNER_STYLE: &str = "position: absolute; top: 40%; width: 100%; text-align: center; \
+    font-size: 5cqw; letter-spacing: 0.2em;";
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct PlayerHud {
+    pub score: u32,
+    // share of a full tank; None while fuel is unlimited
+    pub fuel: Option<f32>,
+    // None while torpedoes are unlimited
+    pub torpedoes: Option<u32>,
+    // shares of full shields and hull; None while any hit is fatal
+    pub shields: Option<f32>,
+    pub hull: Option<f32>,
+    // hyperspace jumps left; None while the rules allow none
+    pub jumps: Option<u32>,
+    pub in_hyperspace: bool,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Hud {
+    pub players: [PlayerHud; 2],
+    pub round: u32,
+    pub best_of: u32,
+    // whole seconds left on the round clock, rounded up
+    pub clock: u32,
+    // what's going on, when it isn't just play
+    pub banner: Option<Banner>,
+}
+
+// Said across the middle of the arena.  Kept apart from the wording, which needs the
+// players' names.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Banner {
+    Countdown { round: u32, seconds: u32 },
+    RoundTo(usize),
+    BothDestroyed,
+    Time,
+    MatchTo(usize),
+    Draw,
+}
+
+impl Hud {
+    pub fn from_game_state(game_state: &GameState) -> Self {
+        let rules = &game_state.config.rules;
+        let capacity = rules.ship.fuel;
+        let player = |idx: usize| {
+            let player = &game_state.players[idx];
+            PlayerHud {
+                score: game_state.scores[idx],
+                fuel: player.fuel.zip(capacity).map(|(left, full)| share(left, full)),
+                shields: player.health.zip(rules.damage).map(|(h, d)| energy_share(h.shields, d.shields)),
+                hull: player.health.zip(rules.damage).map(|(h, d)| energy_share(h.hull.max(0.0), d.hull)),
+                torpedoes: player.torpedoes,
+                jumps: (rules.hyperspace.jumps > 0)
+                    .then(|| rules.hyperspace.jumps.saturating_sub(player.hyperspace.jumps)),
+                in_hyperspace: player.alive && !player.present(),
+            }
+        };
+        Self {
+            players: [player(PLAYER_ONE), player(PLAYER_TWO)],
+            round: game_state.round,
+            best_of: game_state.config.rules.scoring.best_of,
+            clock: seconds(game_state.clock),
+            banner: banner(game_state),
+        }
+    }
+}
+
+// In whole percent, rounded up so the tank only reads empty once it is; a finer share
+// would re-render the overlay on every tick of thrust.
+fn share(left: u32, full: u32) -> f32 {
+    (left * 100).div_ceil(full) as f32 / 100.0
+}
+
+// and the same for shields and hull, which recharge a little every tick
+fn energy_share(left: f32, full: f32) -> f32 {
+    match full > 0.0 {
+        true => (left / full * 100.0).ceil() / 100.0,
+        false => 0.0,
+    }
+}
+
+fn seconds(ticks: u32) -> u32 {
+    (ticks * TICK_MILLIS).div_ceil(1000)
+}
+
+fn clock_text(seconds: u32) -> String {
+    format!("{}:{:02}", seconds / 60, seconds % 60)
+}
+
+fn banner(game_state: &GameState) -> Option<Banner> {
+    let [one, two] = [&game_state.players[PLAYER_ONE], &game_state.players[PLAYER_TWO]];
+    let countdown_ticks = game_state.config.rules.round.countdown_ticks;
+    match game_state.phase {
+        RoundPhase::Countdown => Some(Banner::Countdown {
+            round: game_state.round,
+            seconds: seconds(countdown_ticks.saturating_sub(game_state.phase_ticks)),
+        }),
+        RoundPhase::Playing => None,
+        RoundPhase::RoundOver => Some(match (one.alive, two.alive) {
+            (true, false) => Banner::RoundTo(PLAYER_ONE),
+            (false, true) => Banner::RoundTo(PLAYER_TWO),
+            (false, false) => Banner::BothDestroyed,
+            (true, true) => Banner::Time,
+        }),
+        RoundPhase::MatchOver => Some(match game_state.result {
+            MatchResult::Winner(idx) => Banner::MatchTo(idx),
+            MatchResult::Draw | MatchResult::Undecided => Banner::Draw,
+        }),
+    }
+}
+
+impl Banner {
+    fn text(&self, names: &[String; 2]) -> String {
+        match self {
+            Banner::Countdown { round, seconds } => format!("ROUND {}  {}", round, seconds),
+            Banner::RoundTo(idx) => format!("{} TAKES THE ROUND", names[*idx].to_uppercase()),
+            Banner::BothDestroyed => String::from("BOTH DESTROYED"),
+            Banner::Time => String::from("TIME"),
+            Banner::MatchTo(idx) => format!("{} WINS", names[*idx].to_uppercase()),
+            Banner::Draw => String::from("DRAW"),
+        }
+    }
+}
+
+// the player's ship colour, as CSS
+fn player_color(palette: Palette, idx: usize) -> String {
+    let [r, g, b, _] = palette.colors()[idx].map(|c| (c * 255.0).round() as u8);
+    format!("color: rgb({}, {}, {});", r, g, b)
+}
+
+fn supply(value: Option<String>) -> String {
+    value.unwrap_or_else(|| String::from("∞"))
+}
+
+#[derive(Properties, PartialEq)]
+pub struct HudProps {
+    pub hud: Hud,
+    #[prop_or_else(default_names)]
+    pub names: [String; 2],
+    #[prop_or_default]
+    pub palette: Palette,
+}
+
+#[function_component(HudOverlay)]
+pub fn hud_overlay(props: &HudProps) -> Html {
+    let hud = &props.hud;
+    html! {
+        <div style={format!("{} {}", ARENA_OVERLAY_STYLE, HUD_STYLE)}>
+            { for hud.players.iter().enumerate().map(|(idx, player)| html! {
+                <div style={format!("{} {}", PLAYER_STYLES[idx], player_color(props.palette, idx))}>
+                    <div>{ format!("{}  {}", props.names[idx].to_uppercase(), player.score) }</div>
+                    <div>{ format!("FUEL {}", supply(player.fuel.map(|f| format!("{:.0}%", f * 100.0)))) }</div>
+                    <div>{ format!("TORPS {}", supply(player.torpedoes.map(|t| t.to_string()))) }</div>
+                    if let (Some(shields), Some(hull)) = (player.shields, player.hull) {
+                        <div>{ format!("SHIELDS {:.0}%", shields * 100.0) }</div>
+                        <div>{ format!("HULL {:.0}%", hull * 100.0) }</div>
+                    }
+                    if player.in_hyperspace {
+                        <div>{"IN HYPERSPACE"}</div>
+                    } else if let Some(jumps) = player.jumps {
+                        <div>{ format!("JUMPS {}", jumps) }</div>
+                    }
+                </div>
+            }) }
+            <div style={CLOCK_STYLE}>
+                <div>{ clock_text(hud.clock) }</div>
+                <div>{ format!("ROUND {} OF {}", hud.round, hud.best_of) }</div>
+            </div>
+            { for hud.banner.iter().map(|banner| html! { <div style={BANNER_STYLE}>{ banner.text(&props.names) }</div> }) }
+        </div>
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::simulation::{create_game_state, MatchConfig};
+
+    fn names() -> [String; 2] {
+        [String::from("Ada"), String::from("Grace")]
+    }
+
+    fn banner_text(game_state: &GameState) -> Option<String> {
+        Hud::from_game_state(game_state).banner.map(|banner| banner.text(&names()))
+    }
+
+    #[test]
+    fn the_banner_says_what_the_round_is_doing() {
+        let mut game_state = create_game_state(1, MatchConfig::default());
+        assert_eq!(banner_text(&game_state).as_deref(), Some("ROUND 1  2"));
+
+        game_state.phase = RoundPhase::Playing;
+        assert_eq!(banner_text(&game_state), None);
+
+        game_state.phase = RoundPhase::RoundOver;
+        assert_eq!(banner_text(&game_state).as_deref(), Some("TIME"));
+        game_state.players[PLAYER_ONE].alive = false;
+        assert_eq!(banner_text(&game_state).as_deref(), Some("GRACE TAKES THE ROUND"));
+        game_state.players[PLAYER_TWO].alive = false;
+        assert_eq!(banner_text(&game_state).as_deref(), Some("BOTH DESTROYED"));
+
+        game_state.phase = RoundPhase::MatchOver;
+        game_state.result = MatchResult::Winner(PLAYER_ONE);
+        assert_eq!(banner_text(&game_state).as_deref(), Some("ADA WINS"));
+        game_state.result = MatchResult::Draw;
+        assert_eq!(banner_text(&game_state).as_deref(), Some("DRAW"));
+    }
+
+    #[test]
+    fn supplies_only_read_empty_once_they_are() {
+        assert_eq!(share(200, 200), 1.0);
+        assert_eq!(share(199, 200), 1.0);
+        assert_eq!(share(1, 200), 0.01);
+        assert_eq!(share(0, 200), 0.0);
+        // a magazine of 32 goes down in steps that don't land on whole percents
+        assert_eq!(share(31, 32), 0.97);
+        assert_eq!(energy_share(0.1, 60.0), 0.01);
+        assert_eq!(energy_share(0.0, 0.0), 0.0);
+    }
+
+    #[test]
+    fn the_clock_counts_whole_seconds_up() {
+        assert_eq!(seconds(0), 0);
+        assert_eq!(seconds(1), 1);
+        // 62 ticks are 992ms
+        assert_eq!(seconds(62), 1);
+        assert_eq!(seconds(63), 2);
+        assert_eq!(seconds(3750), 60);
+        assert_eq!(clock_text(seconds(3750)), "1:00");
+        assert_eq!(clock_text(65), "1:05");
+        assert_eq!(clock_text(0), "0:00");
+
+        let mut game_state = create_game_state(1, MatchConfig::default());
+        game_state.clock = 1;
+        assert_eq!(Hud::from_game_state(&game_state).clock, 1);
+    }
+}
@@ -0,0 +1,56 @@
+// Where the site starts, and where quitting a game comes back to.  Each game mode is its
+// own address (/play/local, /play/computer), so a mode can be bookmarked or linked to.
+
+use yew::prelude::*;
+use yew_router::prelude::*;
+
+use crate::simulation::GameMode;
+use crate::Route;
+
+const MENU_STYLE: &str = "max-width: 24em; margin: 6em auto; padding: 1.5em 2em; \
+    font-family: monospace; text-align: center; background: #111; color: #e8e8f0; border-radius: 0.5em;";
+const ENTRY_STYLE: &str = "display: block; width: 100%; margin: 1em 0; padding: 0.5em; \
+    font: inherit; font-size: 1.3em;";
+const NOTE_STYLE: &str = "display: block; font-size: 0.6em; opacity: 0.7;";
+const SANDBOX_STYLE: &str = "margin-top: 2em; font-size: 0.9em;";
+const SANDBOX_ENTRY_STYLE: &str = "font: inherit; margin: 0.3em;";
+
+// the old prototypes, kept around to try ideas in
+const SANDBOX: [(&str, Route); 3] = [
+    ("Particles", Route::Particles),
+    ("Game 3", Route::GameThree),
+    ("Game 5", Route::GameFive),
+];
+
+#[function_component(MainMenu)]
+pub fn main_menu() -> Html {
+    let history = use_history().expect("the menu is only shown under the router");
+    let go = |route: Route| {
+        let history = history.clone();
+        Callback::from(move |_: MouseEvent| history.push(route))
+    };
+    html! {
+        <div style={MENU_STYLE}>
+            <h1>{"SPACEWAR"}</h1>
+            <button style={ENTRY_STYLE} onclick={go(Route::Game { mode: GameMode::LocalTwoPlayer })}>
+                {"Two players"}
+                <span style={NOTE_STYLE}>{"one keyboard"}</span>
+            </button>
+            <button style={ENTRY_STYLE} onclick={go(Route::Game { mode: GameMode::VersusComputer })}>
+                {"Against the computer"}
+            </button>
+            <button style={ENTRY_STYLE} disabled=true>
+                {"Online"}
+                <span style={NOTE_STYLE}>{"not yet"}</span>
+            </button>
+            <button style={ENTRY_STYLE} onclick={go(Route::Replay)}>{"Watch a replay"}</button>
+            <button style={ENTRY_STYLE} onclick={go(Route::Settings)}>{"Settings"}</button>
+            <div style={SANDBOX_STYLE}>
+                <div>{"Sandbox"}</div>
+                { for SANDBOX.iter().map(|(label, route)| html! {
+                    <button style={SANDBOX_ENTRY_STYLE} onclick={go(*route)}>{ *label }</button>
+                }) }
+            </div>
+        </div>
+    }
+}
@@ -3,7 +3,12 @@
 // pub mod game;
 // pub mod game_202;
 pub mod game_303;
+pub mod game_host;
+pub mod host;
+pub mod hud;
+pub mod menu;
 pub mod particles;
-pub mod game_404;
 pub mod game_505;
-
+pub mod renderer_fallback;
+pub mod replay_viewer;
+pub mod settings_page;
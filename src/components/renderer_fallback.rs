@@ -0,0 +1,43 @@
+// Shown in place of the arena when the renderer can't start, most often because the
+// browser has no WebGL2 to give.
+
+use yew::prelude::*;
+
+use crate::renderer::RendererError;
+
+const FALLBACK_STYLE: &str = "max-width: 40em; margin: 4em auto; padding: 1.5em 2em; \
+    font-family: sans-serif; line-height: 1.5; background: #111; color: #e8e8f0; border-radius: 0.5em;";
+
+#[derive(Properties, PartialEq)]
+pub struct RendererFallbackProps {
+    pub error: RendererError,
+}
+
+#[function_component(RendererFallback)]
+pub fn renderer_fallback(props: &RendererFallbackProps) -> Html {
+    match &props.error {
+        RendererError::Unavailable => html! {
+            <div style={FALLBACK_STYLE}>
+                <h2>{"Spacewar needs WebGL2"}</h2>
+                <p>{"This browser didn't give the page a WebGL2 context, so there's nothing to draw the game with."}</p>
+                <ul>
+                    <li>{"Use a recent Firefox, Chrome, Edge or Safari (15 or later)."}</li>
+                    <li>{"Turn hardware acceleration on in the browser's settings, if it's off."}</li>
+                    <li>{"Some graphics drivers are blocked by the browser; updating them can help."}</li>
+                </ul>
+                <p>
+                    {"To check what your browser supports, visit "}
+                    <a href="https://get.webgl.org/webgl2/" style="color: #8cf">{"get.webgl.org/webgl2"}</a>
+                    {"."}
+                </p>
+            </div>
+        },
+        error => html! {
+            <div style={FALLBACK_STYLE}>
+                <h2>{"The game couldn't start its renderer"}</h2>
+                <p>{"WebGL2 is there, but setting it up failed. Reloading the page sometimes helps; if it doesn't, please report what's below."}</p>
+                <pre style="white-space: pre-wrap; font-size: 0.85em;">{ error.to_string() }</pre>
+            </div>
+        },
+    }
+}
@@ -0,0 +1,377 @@
+use web_sys::{DragEvent, File, HtmlCanvasElement, HtmlInputElement};
+use yew::{html, Callback, Component, Context, Html, NodeRef, TargetCast};
+
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+
+use crate::components::host::AnimationLoop;
+use crate::components::hud::{Hud, HudOverlay};
+use crate::components::renderer_fallback::RendererFallback;
+use crate::renderer::{
+    Palette, PostQuality, PostSettings, RenderFrame, Renderer, RendererError, StarChart, CANVAS_BOX_STYLE,
+    CANVAS_STYLE,
+};
+use crate::replay::browser::read_replay;
+use crate::replay::Replay;
+use crate::settings::{Settings, SettingsContext};
+use crate::simulation::{state_hash, GameState, MatchResult, SimEvent, TICK_MILLIS};
+
+// Seeking re-simulates from the nearest earlier snapshot rather than from tick zero.
+const SNAPSHOT_INTERVAL: u32 = 300;
+const SPEEDS: [f64; 5] = [0.25, 0.5, 1.0, 2.0, 4.0];
+
+pub enum Msg {
+    Load(File),
+    Loaded(Result<Replay, String>),
+    TogglePause,
+    StepForward,
+    StepBack,
+    Seek(u32),
+    SetSpeed(f64),
+    ToggleStarChart,
+    TogglePost,
+    SetPostQuality(PostQuality),
+    RendererFailed(RendererError),
+    Frame,
+}
+
+struct Playback {
+    replay: Replay,
+    game_state: GameState,
+    snapshots: Vec<GameState>,
+    paused: bool,
+    speed: f64,
+    lag: f64,
+    // Whether re-running the inputs here reproduced the hash the recorder saw.
+    verified: bool,
+}
+
+impl Playback {
+    fn new(replay: Replay) -> Self {
+        let mut game_state = replay.initial_state();
+        let mut snapshots = vec![game_state.clone()];
+        while game_state.tick < replay.tick_count() {
+            let next = game_state.tick + SNAPSHOT_INTERVAL;
+            replay.advance(&mut game_state, next);
+            snapshots.push(game_state.clone());
+        }
+        let verified = state_hash(&game_state) == replay.final_hash;
+        Self {
+            game_state: replay.initial_state(),
+            replay,
+            snapshots,
+            paused: false,
+            speed: 1.0,
+            lag: 0.0,
+            verified,
+        }
+    }
+
+    fn seek(&mut self, tick: u32) {
+        let tick = tick.min(self.replay.tick_count());
+        if tick < self.game_state.tick || tick - self.game_state.tick > SNAPSHOT_INTERVAL {
+            self.game_state = self.snapshots[(tick / SNAPSHOT_INTERVAL) as usize].clone();
+        }
+        self.replay.advance(&mut self.game_state, tick);
+        self.lag = 0.0;
+    }
+
+    fn at_end(&self) -> bool {
+        self.game_state.tick >= self.replay.tick_count()
+    }
+
+    // Plays on by `millis` of wall-clock time, collecting what happened on the way, and
+    // returns how much game time that was.
+    fn advance_by(&mut self, millis: f64, events: &mut Vec<SimEvent>) -> f64 {
+        if self.paused {
+            return 0.0;
+        }
+        let game_millis = millis * self.speed;
+        if self.at_end() {
+            return game_millis;
+        }
+        self.lag += game_millis;
+        let ticks = (self.lag / TICK_MILLIS as f64) as u32;
+        self.lag -= (ticks * TICK_MILLIS) as f64;
+        let target = self.game_state.tick + ticks;
+        while self.game_state.tick < target.min(self.replay.tick_count()) {
+            let next = self.game_state.tick + 1;
+            self.replay.advance(&mut self.game_state, next);
+            events.extend_from_slice(&self.game_state.events);
+        }
+        game_millis
+    }
+}
+
+pub struct ReplayViewer {
+    node_ref: NodeRef,
+    playback: Rc<RefCell<Option<Playback>>>,
+    error: Option<String>,
+    renderer_error: Option<RendererError>,
+    star_chart: Rc<Cell<StarChart>>,
+    post: Rc<Cell<PostSettings>>,
+    // the rest of the look, from the settings
+    particles: f32,
+    palette: Palette,
+    frames: Option<AnimationLoop>,
+}
+
+impl Component for ReplayViewer {
+    type Message = Msg;
+    type Properties = ();
+
+    // The viewer's own buttons start from the settings, but changing them here doesn't
+    // change the settings.
+    fn create(ctx: &Context<Self>) -> Self {
+        let settings = match ctx.link().context::<SettingsContext>(Callback::noop()) {
+            Some((context, _)) => context.settings,
+            None => Rc::new(Settings::default()),
+        };
+        Self {
+            node_ref: NodeRef::default(),
+            playback: Rc::new(RefCell::new(None)),
+            error: None,
+            renderer_error: None,
+            star_chart: Rc::new(Cell::new(settings.graphics.star_chart)),
+            post: Rc::new(Cell::new(settings.graphics.post)),
+            particles: settings.graphics.particles,
+            palette: settings.palette,
+            frames: None,
+        }
+    }
+
+    fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
+        match msg {
+            Msg::Load(file) => {
+                ctx.link().send_future(async move { Msg::Loaded(read_replay(file).await) });
+                false
+            }
+            Msg::Loaded(Ok(replay)) => {
+                *self.playback.borrow_mut() = Some(Playback::new(replay));
+                self.error = None;
+                true
+            }
+            Msg::Loaded(Err(e)) => {
+                self.error = Some(e);
+                true
+            }
+            Msg::ToggleStarChart => {
+                self.star_chart.set(match self.star_chart.get() {
+                    StarChart::Procedural => StarChart::Planetarium,
+                    StarChart::Planetarium => StarChart::Procedural,
+                });
+                true
+            }
+            Msg::TogglePost => {
+                let post = self.post.get();
+                let on = !post.enabled();
+                self.post.set(PostSettings { persistence: on, bloom: on, scanlines: on, curvature: on, ..post });
+                true
+            }
+            Msg::SetPostQuality(quality) => {
+                self.post.set(PostSettings { quality, ..self.post.get() });
+                true
+            }
+            Msg::RendererFailed(e) => {
+                self.renderer_error = Some(e);
+                true
+            }
+            msg => {
+                let mut playback = self.playback.borrow_mut();
+                let playback = match playback.as_mut() {
+                    Some(playback) => playback,
+                    None => return false,
+                };
+                match msg {
+                    Msg::TogglePause => playback.paused = !playback.paused,
+                    Msg::StepForward => {
+                        playback.paused = true;
+                        let tick = playback.game_state.tick + 1;
+                        playback.seek(tick);
+                    }
+                    Msg::StepBack => {
+                        playback.paused = true;
+                        let tick = playback.game_state.tick.saturating_sub(1);
+                        playback.seek(tick);
+                    }
+                    Msg::Seek(tick) => playback.seek(tick),
+                    Msg::SetSpeed(speed) => playback.speed = speed,
+                    _ => (),
+                }
+                true
+            }
+        }
+    }
+
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        if let Some(e) = &self.renderer_error {
+            return html! { <RendererFallback error={e.clone()} /> };
+        }
+        let link = ctx.link();
+        let on_pick = link.batch_callback(|e: web_sys::Event| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            input.files().and_then(|files| files.get(0)).map(Msg::Load)
+        });
+        let on_drop = link.batch_callback(|e: DragEvent| {
+            e.prevent_default();
+            e.data_transfer()
+                .and_then(|dt| dt.files())
+                .and_then(|files| files.get(0))
+                .map(Msg::Load)
+        });
+        let on_drag_over = |e: DragEvent| e.prevent_default();
+
+        html! {
+            <div ondrop={on_drop} ondragover={on_drag_over}>
+                <div>
+                    <input type="file" accept=".swrp" onchange={on_pick} />
+                    <span>{" or drop a replay file anywhere on the page"}</span>
+                </div>
+                { self.view_error() }
+                { self.view_controls(ctx) }
+                <div style={CANVAS_BOX_STYLE}>
+                    <canvas style={CANVAS_STYLE} ref={self.node_ref.clone()} />
+                    { self.view_hud() }
+                </div>
+            </div>
+        }
+    }
+
+    fn rendered(&mut self, ctx: &Context<Self>, first_render: bool) {
+        if first_render {
+            self.start_render_loop(ctx);
+        }
+    }
+
+    fn destroy(&mut self, _ctx: &Context<Self>) {
+        self.frames = None;
+    }
+}
+
+impl ReplayViewer {
+    fn view_error(&self) -> Html {
+        match &self.error {
+            Some(e) => html! { <p style="color: #b00">{ e }</p> },
+            None => html! {},
+        }
+    }
+
+    // re-rendered on every tick the playback moves
+    fn view_hud(&self) -> Html {
+        match self.playback.borrow().as_ref() {
+            Some(playback) => html! {
+                <HudOverlay hud={Hud::from_game_state(&playback.game_state)} palette={self.palette} />
+            },
+            None => html! {},
+        }
+    }
+
+    fn view_controls(&self, ctx: &Context<Self>) -> Html {
+        let playback = self.playback.borrow();
+        let playback = match playback.as_ref() {
+            Some(playback) => playback,
+            None => return html! {},
+        };
+        let link = ctx.link();
+        let tick = playback.game_state.tick;
+        let ticks = playback.replay.tick_count();
+        let on_scrub = link.callback(|e: web_sys::InputEvent| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            Msg::Seek(input.value().parse().unwrap_or(0))
+        });
+        let post = self.post.get();
+        let result = match playback.game_state.result {
+            MatchResult::Undecided => String::from("undecided"),
+            MatchResult::Winner(idx) => format!("player {} wins", idx + 1),
+            MatchResult::Draw => String::from("draw"),
+        };
+
+        html! {
+            <div>
+                <button onclick={link.callback(|_| Msg::StepBack)}>{"◀|"}</button>
+                <button onclick={link.callback(|_| Msg::TogglePause)}>
+                    { if playback.paused { "Play" } else { "Pause" } }
+                </button>
+                <button onclick={link.callback(|_| Msg::StepForward)}>{"|▶"}</button>
+                <input type="range" min="0" max={ticks.to_string()} value={tick.to_string()}
+                    oninput={on_scrub} style="width: 40em" />
+                <span>{ format!(" tick {} / {} ", tick, ticks) }</span>
+                { for SPEEDS.iter().map(|speed| {
+                    let speed = *speed;
+                    let selected = playback.speed == speed;
+                    html! {
+                        <button disabled={selected} onclick={link.callback(move |_| Msg::SetSpeed(speed))}>
+                            { format!("{}x", speed) }
+                        </button>
+                    }
+                }) }
+                <button onclick={link.callback(|_| Msg::ToggleStarChart)}>
+                    { match self.star_chart.get() {
+                        StarChart::Procedural => "Planetarium",
+                        StarChart::Planetarium => "Random stars",
+                    } }
+                </button>
+                <button onclick={link.callback(|_| Msg::TogglePost)}>
+                    { if post.enabled() { "Plain display" } else { "CRT display" } }
+                </button>
+                { for PostQuality::ALL.iter().filter(|_| post.enabled()).map(|quality| {
+                    let quality = *quality;
+                    html! {
+                        <button disabled={post.quality == quality}
+                            onclick={link.callback(move |_| Msg::SetPostQuality(quality))}>
+                            { format!("{:?}", quality) }
+                        </button>
+                    }
+                }) }
+                <span>{ format!(" seed {}, {}", playback.replay.seed, result) }</span>
+                { if playback.verified {
+                    html! {}
+                } else {
+                    html! { <span style="color: #b00">{" (replay does not reproduce the recorded result)"}</span> }
+                } }
+            </div>
+        }
+    }
+
+    fn start_render_loop(&mut self, ctx: &Context<Self>) {
+        let canvas = self.node_ref.cast::<HtmlCanvasElement>().unwrap();
+        let mut renderer = match Renderer::from_canvas(&canvas) {
+            Ok(renderer) => renderer,
+            Err(e) => return ctx.link().send_message(Msg::RendererFailed(e)),
+        };
+
+        let playback = self.playback.clone();
+        let star_chart = self.star_chart.clone();
+        let post = self.post.clone();
+        let (particles, palette) = (self.particles, self.palette);
+        let frame = ctx.link().callback(|_| Msg::Frame);
+        let mut last_timestamp: Option<f64> = None;
+
+        self.frames = Some(AnimationLoop::start(move |timestamp| {
+            let elapsed = timestamp - last_timestamp.unwrap_or(timestamp);
+            last_timestamp = Some(timestamp);
+            // playback holds still while the WebGL context is lost
+            if !renderer.ready() {
+                return;
+            }
+
+            let mut moved = false;
+            if let Some(playback) = playback.borrow_mut().as_mut() {
+                let before = playback.game_state.tick;
+                let mut events = vec![];
+                let game_elapsed = playback.advance_by(elapsed, &mut events) as f32 * 0.001;
+                let mut render_frame = RenderFrame::from_game_state(&playback.game_state, &events, game_elapsed);
+                render_frame.star_chart = star_chart.get();
+                render_frame.post = post.get();
+                render_frame.palette = palette;
+                render_frame.scale_particles(particles);
+                renderer.draw(&render_frame);
+                moved = playback.game_state.tick != before;
+            }
+            // outside the borrow: the component reads the playback state while re-rendering
+            if moved {
+                frame.emit(());
+            }
+        }));
+    }
+}
@@ -0,0 +1,307 @@
+// The settings page.  Every change is saved as it's made; a game picks them up when it
+// starts.
+
+use wasm_bindgen_futures::spawn_local;
+use web_sys::{Event, HtmlInputElement, InputEvent, KeyboardEvent, MouseEvent};
+use yew::prelude::*;
+use yew_router::prelude::*;
+
+use crate::renderer::{Palette, PostQuality, PostSettings, StarChart};
+use crate::settings::browser::read_rules;
+use crate::settings::{Settings, SettingsContext};
+use crate::simulation::{
+    presets, GameRules, ShipDesign, FIRE, HYPERSPACE, ROTATE_LEFT, ROTATE_RIGHT, THRUST, TICK_MILLIS,
+};
+use crate::Route;
+
+const PAGE_STYLE: &str = "max-width: 40em; margin: 3em auto; padding: 1.5em 2em; font-family: monospace; \
+    background: #111; color: #e8e8f0; border-radius: 0.5em;";
+const ROW_STYLE: &str = "margin: 0.6em 0;";
+const LABEL_STYLE: &str = "display: inline-block; width: 12em;";
+const KEY_STYLE: &str = "min-width: 9em; margin-right: 0.3em;";
+const NOTE_STYLE: &str = "margin-left: 12em; opacity: 0.7;";
+const ERROR_STYLE: &str = "margin-left: 12em; color: #ff7a7a;";
+
+const ESCAPE: u32 = 27;
+
+const CONTROLS: [(u8, &str); 5] = [
+    (ROTATE_LEFT, "Turn left"),
+    (ROTATE_RIGHT, "Turn right"),
+    (THRUST, "Thrust"),
+    (FIRE, "Fire"),
+    (HYPERSPACE, "Hyperspace"),
+];
+const PARTICLES: [(f32, &str); 4] = [(0.25, "25%"), (0.5, "50%"), (1.0, "100%"), (1.5, "150%")];
+// picks one of the post effects' switches out of the settings
+type Effect = fn(&mut PostSettings) -> &mut bool;
+
+const BEST_OF: [(u32, &str); 4] = [(1, "One round"), (3, "Best of 3"), (5, "Best of 5"), (7, "Best of 7")];
+const ROUND_SECONDS: [(u32, &str); 4] = [(30, "30 s"), (60, "1 min"), (90, "90 s"), (120, "2 min")];
+
+// A callback that makes `edit` to a copy of the settings and hands it on to be saved.
+fn changer<E: 'static>(context: &SettingsContext, edit: impl Fn(&mut Settings, E) + 'static) -> Callback<E> {
+    let (settings, update) = (context.settings.clone(), context.update.clone());
+    Callback::from(move |event: E| {
+        let mut changed = (*settings).clone();
+        edit(&mut changed, event);
+        update.emit(changed);
+    })
+}
+
+// One button per option, the current one disabled.
+fn choices<T: PartialEq + Copy + 'static>(options: &[(T, &str)], current: T, pick: Callback<T>) -> Html {
+    html! {
+        { for options.iter().map(|(option, label)| {
+            let option = *option;
+            let pick = pick.clone();
+            html! {
+                <button disabled={option == current} onclick={Callback::from(move |_: MouseEvent| pick.emit(option))}>
+                    { *label }
+                </button>
+            }
+        }) }
+    }
+}
+
+fn row(label: &str, content: Html) -> Html {
+    html! {
+        <div style={ROW_STYLE}>
+            <span style={LABEL_STYLE}>{ label.to_string() }</span>
+            { content }
+        </div>
+    }
+}
+
+// What's printed on the key, for the key codes anyone is likely to pick.
+fn key_name(code: u32) -> String {
+    match code {
+        32 => String::from("Space"),
+        37 => String::from("←"),
+        38 => String::from("↑"),
+        39 => String::from("→"),
+        40 => String::from("↓"),
+        13 => String::from("Enter"),
+        16 => String::from("Shift"),
+        17 => String::from("Ctrl"),
+        18 => String::from("Alt"),
+        48..=57 | 65..=90 => char::from(code as u8).to_string(),
+        96..=105 => format!("Num {}", code - 96),
+        186 => String::from(";"),
+        188 => String::from(","),
+        190 => String::from("."),
+        191 => String::from("/"),
+        code => format!("key {}", code),
+    }
+}
+
+#[function_component(SettingsPage)]
+pub fn settings_page() -> Html {
+    let context = use_context::<SettingsContext>().expect("no settings context");
+    let history = use_history().expect("the settings are only shown under the router");
+    // the player and control waiting for a key
+    let rebinding = use_state(|| None::<(usize, u8)>);
+    // why the last rules file was refused
+    let rules_error = use_state(|| None::<String>);
+    let settings = context.settings.clone();
+
+    let players = (0..2).map(|idx| {
+        let on_name = changer(&context, move |s, e: InputEvent| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            s.names[idx] = input.value();
+        });
+        let keys = settings.keys[idx];
+        let bindings = CONTROLS.iter().map(|(control, label)| {
+            let control = *control;
+            let code = keys.controls().iter().find(|(_, c)| *c == control).map_or(0, |(code, _)| *code);
+            let waiting = *rebinding == Some((idx, control));
+            let on_click = {
+                let rebinding = rebinding.clone();
+                Callback::from(move |_: MouseEvent| rebinding.set(Some((idx, control))))
+            };
+            let on_key = {
+                let rebinding = rebinding.clone();
+                let bind = changer(&context, move |s, code: u32| s.keys[idx].bind(control, code));
+                Callback::from(move |e: KeyboardEvent| {
+                    if *rebinding != Some((idx, control)) {
+                        return;
+                    }
+                    e.prevent_default();
+                    // Esc is pause, and backs out of binding
+                    if e.key_code() != ESCAPE {
+                        bind.emit(e.key_code());
+                    }
+                    rebinding.set(None);
+                })
+            };
+            html! {
+                <div style={ROW_STYLE}>
+                    <span style={LABEL_STYLE}>{ *label }</span>
+                    <button style={KEY_STYLE} onclick={on_click} onkeydown={on_key}>
+                        { if waiting { String::from("press a key…") } else { key_name(code) } }
+                    </button>
+                </div>
+            }
+        });
+        html! {
+            <>
+                <h3>{ format!("Player {}", idx + 1) }</h3>
+                { row("Name", html! {
+                    <input value={settings.names[idx].clone()} oninput={on_name} maxlength="16" />
+                }) }
+                { for bindings }
+                { row("Ship", choices(
+                    &ShipDesign::ALL.map(|design| (design, ship_name(design))),
+                    settings.match_config.ships[idx],
+                    changer(&context, move |s, design| s.match_config.ships[idx] = design),
+                )) }
+            </>
+        }
+    });
+
+    let post = settings.graphics.post;
+    let effects: [(&str, Effect, bool); 4] = [
+        ("Phosphor trails", |p| &mut p.persistence, post.persistence),
+        ("Bloom", |p| &mut p.bloom, post.bloom),
+        ("Scanlines", |p| &mut p.scanlines, post.scanlines),
+        ("Curved screen", |p| &mut p.curvature, post.curvature),
+    ];
+    let on_volume = changer(&context, |s, e: InputEvent| {
+        let input: HtmlInputElement = e.target_unchecked_into();
+        s.volume = input.value().parse::<f32>().unwrap_or(80.0) / 100.0;
+    });
+    let rules = &settings.match_config.rules;
+    let preset_names: Vec<(usize, &str)> = presets().iter().map(|p| p.name.as_str()).enumerate().collect();
+    // none, when the rules came from a file or were changed here
+    let preset = presets().iter().position(|p| p == rules);
+    let on_rules_file = {
+        let rules_error = rules_error.clone();
+        let load = changer(&context, |s, rules: GameRules| s.match_config.rules = rules);
+        Callback::from(move |e: Event| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            let file = match input.files().and_then(|files| files.get(0)) {
+                Some(file) => file,
+                None => return,
+            };
+            // so the same file can be picked again once it's fixed
+            input.set_value("");
+            let (rules_error, load) = (rules_error.clone(), load.clone());
+            spawn_local(async move {
+                match read_rules(file).await {
+                    Ok(rules) => {
+                        rules_error.set(None);
+                        load.emit(rules);
+                    }
+                    Err(e) => rules_error.set(Some(e)),
+                }
+            });
+        })
+    };
+    let round_seconds = rules.round.round_ticks * TICK_MILLIS / 1000;
+    let reset = changer(&context, |s, _: MouseEvent| *s = Settings::default());
+    let back = Callback::from(move |_: MouseEvent| history.push(Route::Menu));
+
+    html! {
+        <div style={PAGE_STYLE}>
+            <h1>{"Settings"}</h1>
+
+            <h2>{"Players"}</h2>
+            { for players }
+
+            <h2>{"Graphics"}</h2>
+            { for effects.iter().map(|&(label, effect, on)| row(label, choices(
+                &[(true, "On"), (false, "Off")],
+                on,
+                changer(&context, move |s, on| *effect(&mut s.graphics.post) = on),
+            ))) }
+            { row("Quality", choices(
+                &PostQuality::ALL.map(|quality| (quality, quality_name(quality))),
+                post.quality,
+                changer(&context, |s, quality| s.graphics.post.quality = quality),
+            )) }
+            { row("Stars", choices(
+                &[(StarChart::Procedural, "Random"), (StarChart::Planetarium, "Planetarium")],
+                settings.graphics.star_chart,
+                changer(&context, |s, chart| s.graphics.star_chart = chart),
+            )) }
+            { row("Particles", choices(
+                &PARTICLES,
+                settings.graphics.particles,
+                changer(&context, |s, particles| s.graphics.particles = particles),
+            )) }
+            { row("Colours", choices(
+                &Palette::ALL.map(|palette| (palette, palette_name(palette))),
+                settings.palette,
+                changer(&context, |s, palette| s.palette = palette),
+            )) }
+
+            <h2>{"Sound"}</h2>
+            { row("Volume", html! {
+                <>
+                    <input type="range" min="0" max="100" value={((settings.volume * 100.0).round()).to_string()}
+                        oninput={on_volume} />
+                    <span>{" (nothing makes a sound yet)"}</span>
+                </>
+            }) }
+
+            <h2>{"Rules"}</h2>
+            { row("Rules", html! {
+                <>
+                    { choices(
+                        &preset_names,
+                        preset.unwrap_or(usize::MAX),
+                        changer(&context, |s, idx: usize| s.match_config.rules = presets()[idx].clone()),
+                    ) }
+                    <input type="file" accept=".ron,.json" onchange={on_rules_file} />
+                </>
+            }) }
+            if preset.is_none() {
+                <div style={NOTE_STYLE}>{ format!("Custom: {}", rules.name) }</div>
+            }
+            if let Some(e) = &*rules_error {
+                <div style={ERROR_STYLE}>{ e.clone() }</div>
+            }
+            { row("Rounds", choices(
+                &BEST_OF,
+                rules.scoring.best_of,
+                changer(&context, |s, best_of| s.match_config.rules.scoring.best_of = best_of),
+            )) }
+            { row("Round length", choices(
+                &ROUND_SECONDS,
+                round_seconds,
+                changer(&context, |s, seconds: u32| {
+                    s.match_config.rules.round.round_ticks = seconds * 1000 / TICK_MILLIS
+                }),
+            )) }
+
+            <div style="margin-top: 2em;">
+                <button onclick={reset}>{"Reset to defaults"}</button>
+                {" "}
+                <button onclick={back}>{"Back to menu"}</button>
+            </div>
+        </div>
+    }
+}
+
+fn ship_name(design: ShipDesign) -> &'static str {
+    match design {
+        ShipDesign::Dart => "Dart",
+        ShipDesign::Needle => "Needle",
+        ShipDesign::Wedge => "Wedge",
+    }
+}
+
+fn quality_name(quality: PostQuality) -> &'static str {
+    match quality {
+        PostQuality::Low => "Low",
+        PostQuality::Medium => "Medium",
+        PostQuality::High => "High",
+    }
+}
+
+fn palette_name(palette: Palette) -> &'static str {
+    match palette {
+        Palette::Classic => "Classic",
+        Palette::Phosphor => "Phosphor",
+        Palette::Contrast => "High contrast",
+    }
+}
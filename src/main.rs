@@ -1,12 +1,15 @@
 #![recursion_limit = "512"]
 
 mod components;
+mod renderer;
+mod replay;
 mod services;
+mod settings;
+mod simulation;
 
 use std::cell::RefCell;
 use std::rc::Rc;
 
-use wasm_bindgen::prelude::*;
 use yew::functional::*;
 use yew::prelude::*;
 use yew_router::prelude::*;
@@ -16,12 +19,16 @@ use yew_router::prelude::*;
 // use components::game_202::{GameTwo};
 use components::game_303::{GameThree};
 use components::particles::Particles;
-use components::game_404::{GameFour};
+use components::game_host::GameHost;
+use components::menu::MainMenu;
 use components::game_505::{GameFive};
+use components::replay_viewer::ReplayViewer;
+use components::settings_page::SettingsPage;
+use settings::browser::{load_settings, save_settings};
+use settings::{Settings, SettingsContext};
+use simulation::{GameMode, MatchConfig};
 
 
-use wasm_logger;
-
 #[cfg(feature = "wee_alloc")]
 #[global_allocator]
 static ALLOC: wee_alloc::WeeAlloc = wee_alloc::WeeAlloc::INIT;
@@ -33,11 +40,17 @@ pub enum Route {
     // #[at("/")]
     // GameTwo,
     #[at("/")]
-    GameFour,
+    Menu,
+    #[at("/play/:mode")]
+    Game { mode: GameMode },
     #[at("/game_5")]
     GameFive,
     #[at("/game_3_old")]
     GameThree,
+    #[at("/replay")]
+    Replay,
+    #[at("/settings")]
+    Settings,
     // #[at("/login")]
     // Login,
     // #[at("/chat")]
@@ -61,24 +74,62 @@ fn main() -> Html {
             username: RefCell::new("initial".into()),
         })
     });
+    let settings = use_state(|| Rc::new(load_settings()));
+    let settings_ctx = SettingsContext {
+        settings: (*settings).clone(),
+        update: {
+            let settings = settings.clone();
+            Callback::from(move |changed: Settings| {
+                save_settings(&changed);
+                settings.set(Rc::new(changed));
+            })
+        },
+    };
+    providers((*ctx).clone(), settings_ctx)
+}
+
+// yew 0.19's html! expands these component tags into statements that clippy takes for
+// no-ops; outside a function component the lint can be allowed.
+#[allow(clippy::unnecessary_operation)]
+fn providers(user: User, settings_ctx: SettingsContext) -> Html {
     html! {
-        <ContextProvider<User> context={(*ctx).clone()}>
-            <BrowserRouter>
-                <div>
-                    <Switch<Route> render={Switch::render(switch)}/>
-                </div>
-            </BrowserRouter>
+        <ContextProvider<User> context={user}>
+            <ContextProvider<SettingsContext> context={settings_ctx}>
+                <BrowserRouter>
+                    <div>
+                        <Switch<Route> render={Switch::render(switch)}/>
+                    </div>
+                </BrowserRouter>
+            </ContextProvider<SettingsContext>>
         </ContextProvider<User>>
     }
 }
 
+#[derive(Properties, PartialEq)]
+struct GameRouteProps {
+    mode: GameMode,
+}
+
+// A new game in `mode`, with the ships and rules as set in the settings.
+#[function_component(GameRoute)]
+fn game_route(props: &GameRouteProps) -> Html {
+    let settings = use_context::<SettingsContext>().expect("no settings context").settings;
+    let config = MatchConfig { mode: props.mode, ..settings.match_config.clone() };
+    html! { <GameHost {config} {settings} /> }
+}
+
+// and components without props into let-bindings of ()
+#[allow(clippy::let_unit_value)]
 fn switch(selected_route: &Route) -> Html {
     match selected_route {
         Route::Particles => html! {<Particles />},
         // Route::GameTwo => html! {<GameTwo />},
         Route::GameThree => html! {<GameThree />},
-        Route::GameFour => html! {<GameFour />},
+        Route::Menu => html! {<MainMenu />},
+        Route::Game { mode } => html! {<GameRoute mode={*mode} />},
+        Route::Settings => html! {<SettingsPage />},
         Route::GameFive => html! {<GameFive />},
+        Route::Replay => html! {<ReplayViewer />},
         // Route::Login => html! {<Login />},
         // Route::Chat => html! {<Chat/>},
         Route::NotFound => html! {<h1>{"404"}</h1>},
@@ -0,0 +1,64 @@
+// What `Renderer` asks of the GPU, as a trait.  `WebGlBackend` (webgl.rs) does it for
+// real; in tests `RecordingBackend` (recording.rs) writes it all down instead, so the
+// renderer's side of a frame (what gets drawn, in what order, with which program,
+// uniforms and instances) can be checked without a browser.
+//
+// The calls are coarse on purpose: the sky, the particles and the post effects each
+// keep their own buffers and programs behind one call, carrying the uniforms that change
+// from frame to frame.
+
+use cgmath::Matrix4;
+
+use super::errors::RendererError;
+use super::frame::{MeshId, ParticleBurst};
+use super::gravity::GravityField;
+use super::post::{PostQuality, PostSettings};
+use super::shaders::ShaderSource;
+use super::starfield::StarChart;
+use super::viewport::Viewport;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum DrawCall {
+    // the nebula, then the stars twinkling at `time` seconds; `point_scale` is the arena's
+    // side in pixels
+    Sky { seed: u64, chart: StarChart, time: f32, point_scale: f32 },
+    // spawns `bursts`, then draws every live particle and moves it on by `delta` seconds
+    Particles { bursts: Vec<ParticleBurst>, gravity: GravityField, delta: f32, point_size: f32 },
+    // one instanced draw of `mesh`; `instances` are INSTANCE_FLOATS each
+    Sprites { vert: ShaderSource, frag: ShaderSource, mesh: MeshId, instances: Vec<f32> },
+    // phosphor and bloom on the offscreen scene, `elapsed` seconds after the last
+    PostProcess { settings: PostSettings, elapsed: f32 },
+    // the processed scene onto the arena, `arena_side` pixels across
+    Composite { settings: PostSettings, arena_side: i32 },
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ContextStatus {
+    Ready,
+    // nothing drawn now shows; lost for good if it came back and couldn't be rebuilt
+    Lost,
+    // back and rebuilt since the last call, so whatever was sent before is gone
+    Restored,
+}
+
+pub trait RenderBackend {
+    // The canvas's new size, if it changed since the last call.
+    fn take_resize(&mut self) -> Option<Viewport>;
+    fn context_status(&mut self) -> ContextStatus;
+
+    fn bind_canvas(&mut self);
+    // The offscreen scene for an arena `arena_side` pixels across, `quality.scene_side`
+    // pixels itself.
+    fn bind_scene(&mut self, arena_side: i32, quality: PostQuality) -> Result<(), RendererError>;
+
+    // x, y, width, height in pixels from the bottom left
+    fn set_viewport(&mut self, rect: [i32; 4]);
+    // None to draw anywhere in the viewport
+    fn set_scissor(&mut self, rect: Option<[i32; 4]>);
+    fn clear(&mut self, color: [f32; 4]);
+
+    // for every program with a Camera block
+    fn set_projection(&mut self, projection: Matrix4<f32>);
+
+    fn draw(&mut self, call: DrawCall);
+}
@@ -0,0 +1,50 @@
+// WebGL context loss.  Browsers drop the context when the GPU resets or the laptop
+// switches GPUs, and every program, buffer and VAO goes with it.  The browser only
+// offers it back if the page says it wants it (preventDefault on `webglcontextlost`);
+// once `webglcontextrestored` fires, the backend rebuilds everything from the same
+// sources, meshes and sizes it started from.
+
+use std::cell::Cell;
+use std::rc::Rc;
+
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+use web_sys::{Event, HtmlCanvasElement};
+
+const LOST: &str = "webglcontextlost";
+const RESTORED: &str = "webglcontextrestored";
+
+// Listens on the canvas until dropped.
+pub struct ContextWatcher {
+    canvas: HtmlCanvasElement,
+    on_lost: Closure<dyn FnMut(Event)>,
+    on_restored: Closure<dyn FnMut(Event)>,
+    restored: Rc<Cell<bool>>,
+}
+
+impl ContextWatcher {
+    pub fn new(canvas: &HtmlCanvasElement) -> Self {
+        let restored = Rc::new(Cell::new(false));
+        let on_lost = Closure::wrap(Box::new(|event: Event| event.prevent_default()) as Box<dyn FnMut(Event)>);
+        let flag = restored.clone();
+        let on_restored = Closure::wrap(Box::new(move |_: Event| flag.set(true)) as Box<dyn FnMut(Event)>);
+        for (name, callback) in [(LOST, &on_lost), (RESTORED, &on_restored)] {
+            // only fails for a callback that isn't a function
+            let _ = canvas.add_event_listener_with_callback(name, callback.as_ref().unchecked_ref());
+        }
+        Self { canvas: canvas.clone(), on_lost, on_restored, restored }
+    }
+
+    // Whether the context came back since the last call.
+    pub fn take_restored(&self) -> bool {
+        self.restored.replace(false)
+    }
+}
+
+impl Drop for ContextWatcher {
+    fn drop(&mut self) {
+        for (name, callback) in [(LOST, &self.on_lost), (RESTORED, &self.on_restored)] {
+            let _ = self.canvas.remove_event_listener_with_callback(name, callback.as_ref().unchecked_ref());
+        }
+    }
+}
@@ -0,0 +1,86 @@
+// GPU particle effects: one `GpuParticleSystem` holding every effect's particles.  New
+// particles are written over the oldest slots of a fixed-capacity ring, so bursts can
+// overlap freely and the oldest quietly make way when it fills up.
+
+use std::cell::{Cell, RefCell};
+
+use web_sys::{WebGl2RenderingContext as GL, WebGlProgram, WebGlUniformLocation};
+
+use super::errors::RendererError;
+use super::frame::ParticleBurst;
+use super::particle_system::{GpuParticleSystem, ParticleAttribute};
+use super::shaders::{ProgramBuilder, EFFECTS_FRAG, EFFECTS_VERT};
+use crate::simulation::rng::Rng;
+
+pub const PARTICLE_CAPACITY: usize = 8192;
+
+// matching effects.vert's inputs and, in the same order, its captured outputs
+const LAYOUT: [ParticleAttribute; 5] = [
+    ParticleAttribute { location: 0, size: 2 }, // position
+    ParticleAttribute { location: 1, size: 2 }, // velocity
+    ParticleAttribute { location: 2, size: 1 }, // age
+    ParticleAttribute { location: 3, size: 1 }, // lifetime
+    ParticleAttribute { location: 4, size: 4 }, // color
+];
+const VARYINGS: [&str; 5] = ["v_position", "v_velocity", "v_age", "v_lifetime", "v_color"];
+
+// The sprays only have to look random, so one fixed seed does for every game.
+const SPRAY_SEED: u64 = 0x5eed_5b7a;
+
+pub struct ParticleEffects {
+    program: WebGlProgram,
+    delta_loc: Option<WebGlUniformLocation>,
+    point_size_loc: Option<WebGlUniformLocation>,
+    particles: GpuParticleSystem,
+    // next ring slot to spawn into
+    cursor: Cell<usize>,
+    rng: RefCell<Rng>,
+}
+
+impl ParticleEffects {
+    pub fn new(gl: &GL) -> Result<Self, RendererError> {
+        let program = ProgramBuilder::new(gl, EFFECTS_VERT, EFFECTS_FRAG)
+            .transform_feedback_varyings(&VARYINGS, GL::INTERLEAVED_ATTRIBS)
+            .build()?;
+
+        // zeroed slots have no lifetime, so they start out dead
+        Ok(Self {
+            delta_loc: gl.get_uniform_location(&program, "u_delta"),
+            point_size_loc: gl.get_uniform_location(&program, "u_point_size"),
+            program,
+            particles: GpuParticleSystem::new(gl, PARTICLE_CAPACITY, &LAYOUT)?,
+            cursor: Cell::new(0),
+            rng: RefCell::new(Rng::new(SPRAY_SEED)),
+        })
+    }
+
+    pub fn spawn(&self, gl: &GL, bursts: &[ParticleBurst]) {
+        let mut particles: Vec<f32> = vec![];
+        let mut rng = self.rng.borrow_mut();
+        for burst in bursts {
+            burst.emit(&mut rng, &mut particles);
+        }
+        let stride = self.particles.stride();
+        let capacity = self.particles.capacity();
+        // more than the whole ring at once would only overwrite itself
+        let start = particles.len().saturating_sub(capacity * stride);
+        let mut particles = &particles[start..];
+
+        while !particles.is_empty() {
+            let cursor = self.cursor.get();
+            let room = (capacity - cursor) * stride;
+            let (chunk, rest) = particles.split_at(room.min(particles.len()));
+            self.particles.write(gl, cursor, chunk);
+            self.cursor.set((cursor + chunk.len() / stride) % capacity);
+            particles = rest;
+        }
+    }
+
+    // Draws every live particle and advances them all by `delta` seconds.
+    pub fn step(&self, gl: &GL, delta: f32, point_size: f32) {
+        gl.use_program(Some(&self.program));
+        gl.uniform1f(self.delta_loc.as_ref(), delta);
+        gl.uniform1f(self.point_size_loc.as_ref(), point_size);
+        self.particles.step(gl);
+    }
+}
@@ -0,0 +1,225 @@
+// Errors from setting up the renderer, and `report` for surfacing them.  A broken shader
+// used to compile "fine", link "fine" and then draw nothing; now the compile and link
+// status is checked and the failure names the file and points at the offending lines.
+
+use std::collections::BTreeSet;
+use std::fmt;
+
+use gloo_console::log;
+
+// lines of source shown either side of each line the driver complains about
+const EXCERPT_CONTEXT: usize = 2;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum ShaderError {
+    // create_shader/create_program handed back nothing, which in practice means the
+    // context is gone
+    Create(&'static str),
+    Compile {
+        name: &'static str,
+        log: String,
+        excerpt: String,
+    },
+    Link {
+        vert: &'static str,
+        frag: &'static str,
+        log: String,
+    },
+}
+
+impl ShaderError {
+    pub fn compile(name: &'static str, code: &str, log: String) -> Self {
+        let excerpt = annotate(code, &error_lines(&log));
+        ShaderError::Compile { name, log, excerpt }
+    }
+}
+
+impl fmt::Display for ShaderError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ShaderError::Create(what) => write!(f, "could not create {}; is the WebGL context lost?", what),
+            ShaderError::Compile { name, log, excerpt } => {
+                write!(f, "{} failed to compile:\n{}", name, log.trim_end())?;
+                if !excerpt.is_empty() {
+                    write!(f, "\n\n{}", excerpt)?;
+                }
+                Ok(())
+            }
+            ShaderError::Link { vert, frag, log } => {
+                write!(f, "{} + {} failed to link:\n{}", vert, frag, log.trim_end())
+            }
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum RendererError {
+    // the browser has no WebGL2, or won't give it to this page
+    Unavailable,
+    Gl(String),
+    Shader(ShaderError),
+}
+
+impl From<ShaderError> for RendererError {
+    fn from(e: ShaderError) -> Self {
+        RendererError::Shader(e)
+    }
+}
+
+impl From<String> for RendererError {
+    fn from(e: String) -> Self {
+        RendererError::Gl(e)
+    }
+}
+
+impl From<&str> for RendererError {
+    fn from(e: &str) -> Self {
+        RendererError::Gl(e.to_string())
+    }
+}
+
+impl fmt::Display for RendererError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RendererError::Unavailable => write!(f, "WebGL2 is not available"),
+            RendererError::Gl(e) => write!(f, "{}", e),
+            RendererError::Shader(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+// Drivers report "ERROR: <source>:<line>: message" (ANGLE, Mesa and friends all agree on
+// this much).  Pull out the line numbers so the excerpt can point at them.
+fn error_lines(log: &str) -> BTreeSet<usize> {
+    log.lines()
+        .filter_map(|line| {
+            let mut parts = line.splitn(4, ':');
+            let _severity = parts.next()?;
+            parts.next()?.trim().parse::<usize>().ok()?;
+            parts.next()?.trim().parse::<usize>().ok()
+        })
+        .collect()
+}
+
+// The source around each flagged line, numbered, with the flagged lines marked:
+//
+//       5 | uniform float u_time;
+//   >   6 | vec2 foo = bar
+//       7 | void main() {
+fn annotate(code: &str, flagged: &BTreeSet<usize>) -> String {
+    let lines: Vec<&str> = code.lines().collect();
+    let shown: BTreeSet<usize> = flagged
+        .iter()
+        .flat_map(|&n| n.saturating_sub(EXCERPT_CONTEXT).max(1)..=n + EXCERPT_CONTEXT)
+        .filter(|&n| n <= lines.len())
+        .collect();
+
+    let mut out: Vec<String> = vec![];
+    let mut previous: Option<usize> = None;
+    for n in shown {
+        if previous.is_some_and(|p| n > p + 1) {
+            out.push(String::from("      ..."));
+        }
+        let marker = if flagged.contains(&n) { '>' } else { ' ' };
+        out.push(format!("{} {:>4} | {}", marker, n, lines[n - 1]));
+        previous = Some(n);
+    }
+    out.join("\n")
+}
+
+// Log the error, and in debug builds also put it on the page where it can't be missed.
+pub fn report(error: &impl fmt::Display) {
+    let message = error.to_string();
+    log!(message.clone());
+    #[cfg(debug_assertions)]
+    show_error_panel(&message);
+}
+
+#[cfg(debug_assertions)]
+fn show_error_panel(message: &str) {
+    const PANEL_ID: &str = "render-error-panel";
+    let document = match web_sys::window().and_then(|w| w.document()) {
+        Some(document) => document,
+        None => return,
+    };
+    let panel = match document.get_element_by_id(PANEL_ID) {
+        Some(panel) => panel,
+        None => {
+            let panel = match document.create_element("pre") {
+                Ok(panel) => panel,
+                Err(_) => return,
+            };
+            panel.set_id(PANEL_ID);
+            let _ = panel.set_attribute(
+                "style",
+                "position: fixed; top: 0; left: 0; right: 0; max-height: 60vh; overflow: auto; \
+                 margin: 0; padding: 1em; z-index: 1000; white-space: pre-wrap; \
+                 background: #300; color: #fdd; font: 13px monospace;",
+            );
+            match document.body() {
+                Some(body) => {
+                    let _ = body.append_child(&panel);
+                }
+                None => return,
+            }
+            panel
+        }
+    };
+    let previous = panel.text_content().unwrap_or_default();
+    let separator = if previous.is_empty() { "" } else { "\n\n" };
+    panel.set_text_content(Some(&format!("{}{}{}", previous, separator, message)));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SOURCE: &str = "#version 300 es\n\
+        precision highp float;\n\
+        in vec2 a_position;\n\
+        uniform vec2 u_offset;\n\
+        uniform float u_time;\n\
+        vec2 shifted = foo + u_offset;\n\
+        void main() {\n\
+            gl_Position = vec4(shifted, 0.0, 1.0);\n\
+        }";
+
+    #[test]
+    fn driver_logs_give_the_flagged_lines() {
+        let log = "ERROR: 0:6: 'foo' : undeclared identifier\n\
+            ERROR: 0:6: '+' : wrong operand types\n\
+            WARNING: 0:9: extension directive ignored\n\
+            ERROR: 2 compilation errors.  No code generated.\n";
+        assert_eq!(error_lines(log), BTreeSet::from([6, 9]));
+    }
+
+    #[test]
+    fn the_excerpt_marks_the_line_with_its_context() {
+        let excerpt = annotate(SOURCE, &BTreeSet::from([6]));
+        let expected = [
+            "     4 | uniform vec2 u_offset;",
+            "     5 | uniform float u_time;",
+            ">    6 | vec2 shifted = foo + u_offset;",
+            "     7 | void main() {",
+            "     8 | gl_Position = vec4(shifted, 0.0, 1.0);",
+        ];
+        assert_eq!(excerpt, expected.join("\n"));
+    }
+
+    #[test]
+    fn lines_far_apart_are_shown_separately_and_clipped_to_the_source() {
+        let excerpt = annotate(SOURCE, &BTreeSet::from([1, 9]));
+        let lines: Vec<&str> = excerpt.lines().collect();
+        assert_eq!(lines.first(), Some(&">    1 | #version 300 es"));
+        assert!(lines.contains(&"      ..."));
+        assert_eq!(lines.last(), Some(&">    9 | }"));
+    }
+
+    #[test]
+    fn an_unparseable_log_still_reports_without_an_excerpt() {
+        let error = ShaderError::compile("broken.frag", SOURCE, String::from("internal compiler error\n"));
+        assert_eq!(error_lines("internal compiler error\nERROR: x:y: nonsense"), BTreeSet::new());
+        assert!(matches!(&error, ShaderError::Compile { excerpt, .. } if excerpt.is_empty()));
+        assert_eq!(error.to_string(), "broken.frag failed to compile:\ninternal compiler error");
+    }
+}
@@ -0,0 +1,341 @@
+// A plain description of one frame: what to draw and where, with no GL in sight.
+// Built from the simulation state each frame and handed to `Renderer::draw`.
+
+use std::f32::consts::PI;
+
+use serde::{Deserialize, Serialize};
+
+use super::gravity::GravityField;
+use super::post::PostSettings;
+use super::starfield::StarChart;
+use crate::simulation::rng::Rng;
+use crate::simulation::{GameState, ShipDesign, SimEvent};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum MeshId {
+    Ship(ShipDesign),
+    Torpedo,
+}
+
+impl MeshId {
+    // draw order, back to front
+    pub const ALL: [MeshId; 4] = [
+        MeshId::Torpedo,
+        MeshId::Ship(ShipDesign::Dart),
+        MeshId::Ship(ShipDesign::Needle),
+        MeshId::Ship(ShipDesign::Wedge),
+    ];
+}
+
+// The players' colours.  Torpedoes take their owner's, and the HUD matches.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Palette {
+    // cyan and amber
+    #[default]
+    Classic,
+    // the greens of an old phosphor screen
+    Phosphor,
+    // white and magenta, far apart for any eyes
+    Contrast,
+}
+
+impl Palette {
+    pub const ALL: [Palette; 3] = [Palette::Classic, Palette::Phosphor, Palette::Contrast];
+
+    // player one's, then player two's
+    pub fn colors(self) -> [[f32; 4]; 2] {
+        match self {
+            Palette::Classic => [[0.35, 0.85, 1.0, 1.0], [1.0, 0.75, 0.3, 1.0]],
+            Palette::Phosphor => [[0.45, 1.0, 0.45, 1.0], [0.8, 1.0, 0.9, 1.0]],
+            Palette::Contrast => [[1.0, 1.0, 1.0, 1.0], [1.0, 0.3, 0.8, 1.0]],
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Sprite {
+    pub mesh: MeshId,
+    pub position: [f32; 2],
+    pub rotation: f32,
+    // the player it belongs to, for its colour
+    pub owner: usize,
+}
+
+// How an effect's particles look and move.  Particles leave at up to `speed` within
+// `spread` radians either side of the burst's heading, and live up to `lifetime` seconds.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Emitter {
+    pub speed: f32,
+    pub spread: f32,
+    pub lifetime: f32,
+    pub color: [f32; 4],
+}
+
+const EXPLOSION: Emitter = Emitter {
+    speed: 0.5,
+    spread: PI,
+    lifetime: 1.2,
+    color: [1.0, 0.45, 0.05, 1.0],
+};
+const EXPLOSION_PARTICLES: u32 = 240;
+
+// a hit the ship lived through
+const SPARKS: Emitter = Emitter {
+    speed: 0.3,
+    spread: PI,
+    lifetime: 0.4,
+    color: [0.6, 0.85, 1.0, 1.0],
+};
+const SPARK_PARTICLES: u32 = 60;
+
+const EXHAUST: Emitter = Emitter {
+    speed: 0.35,
+    spread: 0.3,
+    lifetime: 0.35,
+    color: [0.15, 0.35, 0.95, 0.8],
+};
+const EXHAUST_PARTICLES: u32 = 16;
+// the tail sits this far behind the centre of the ship
+const EXHAUST_OFFSET: f32 = 0.008;
+
+// A ship jumping out leaves a fast, thin flash; coming back, a slower cloud gathers
+// round it.
+const HYPERSPACE_OUT: Emitter = Emitter {
+    speed: 0.7,
+    spread: PI,
+    lifetime: 0.3,
+    color: [0.75, 0.55, 1.0, 1.0],
+};
+const HYPERSPACE_IN: Emitter = Emitter {
+    speed: 0.12,
+    spread: PI,
+    lifetime: 0.8,
+    color: [0.85, 0.8, 1.0, 0.9],
+};
+const HYPERSPACE_PARTICLES: u32 = 120;
+
+// left hanging where the torpedo passed, drifting a little
+const TORPEDO_TRAIL: Emitter = Emitter {
+    speed: 0.01,
+    spread: PI,
+    lifetime: 0.5,
+    color: [0.35, 0.35, 0.35, 0.6],
+};
+const TORPEDO_TRAIL_PER_SECOND: f32 = 90.0;
+
+// `count` particles from one point, on top of a shared `velocity`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ParticleBurst {
+    pub emitter: Emitter,
+    pub count: u32,
+    pub position: [f32; 2],
+    pub velocity: [f32; 2],
+    pub heading: f32,
+}
+
+impl ParticleBurst {
+    pub fn from_event(event: &SimEvent) -> Self {
+        match *event {
+            SimEvent::TorpedoHit { position_dx, position_dy, velocity_dx, velocity_dy, .. }
+            | SimEvent::HyperspaceBreakup { position_dx, position_dy, velocity_dx, velocity_dy, .. }
+            | SimEvent::Rammed { position_dx, position_dy, velocity_dx, velocity_dy, .. }
+            | SimEvent::VehicleCollision { position_dx, position_dy, velocity_dx, velocity_dy } => Self {
+                emitter: EXPLOSION,
+                count: EXPLOSION_PARTICLES,
+                position: [position_dx, position_dy],
+                velocity: [velocity_dx, velocity_dy],
+                heading: 0.0,
+            },
+            SimEvent::Damaged { position_dx, position_dy, velocity_dx, velocity_dy, .. }
+            | SimEvent::Bounce { position_dx, position_dy, velocity_dx, velocity_dy } => Self {
+                emitter: SPARKS,
+                count: SPARK_PARTICLES,
+                position: [position_dx, position_dy],
+                velocity: [velocity_dx, velocity_dy],
+                heading: 0.0,
+            },
+            SimEvent::Hyperspace { position_dx, position_dy, leaving, .. } => Self {
+                emitter: if leaving { HYPERSPACE_OUT } else { HYPERSPACE_IN },
+                count: HYPERSPACE_PARTICLES,
+                position: [position_dx, position_dy],
+                velocity: [0.0, 0.0],
+                heading: 0.0,
+            },
+            SimEvent::Thrust { position_dx, position_dy, velocity_dx, velocity_dy, vifo_theta, .. } => {
+                let heading = vifo_theta + PI;
+                Self {
+                    emitter: EXHAUST,
+                    count: EXHAUST_PARTICLES,
+                    position: [
+                        position_dx + heading.cos() * EXHAUST_OFFSET,
+                        position_dy + heading.sin() * EXHAUST_OFFSET,
+                    ],
+                    velocity: [velocity_dx, velocity_dy],
+                    heading,
+                }
+            }
+        }
+    }
+
+    // Appends each particle's initial state, laid out as effects.vert reads it.  The
+    // spread is cosmetic, so `rng` is the renderer's own rather than the match's.
+    pub fn emit(&self, rng: &mut Rng, particles: &mut Vec<f32>) {
+        let emitter = &self.emitter;
+        for _ in 0..self.count {
+            let angle = self.heading + rng.range(-1.0, 1.0) * emitter.spread;
+            // sqrt keeps the cloud evenly filled rather than bunched at the centre
+            let speed = emitter.speed * rng.next_f32().sqrt();
+            let lifetime = emitter.lifetime * rng.range(0.5, 1.0);
+            particles.extend_from_slice(&[
+                self.position[0],
+                self.position[1],
+                self.velocity[0] + angle.cos() * speed,
+                self.velocity[1] + angle.sin() * speed,
+                0.0,
+                lifetime,
+            ]);
+            particles.extend_from_slice(&emitter.color);
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct RenderFrame {
+    pub sprites: Vec<Sprite>,
+    // spawned this frame
+    pub bursts: Vec<ParticleBurst>,
+    // pulling on the particles
+    pub gravity: GravityField,
+    // seconds of game time since the last frame, to advance the effects by
+    pub elapsed: f32,
+    // the sky comes from the match seed
+    pub seed: u64,
+    pub star_chart: StarChart,
+    pub post: PostSettings,
+    pub palette: Palette,
+}
+
+impl RenderFrame {
+    // `events` are those of every tick run since the last frame.
+    pub fn from_game_state(game_state: &GameState, events: &[SimEvent], elapsed: f32) -> Self {
+        let ships = game_state.players.iter().enumerate().filter(|(_, p)| p.present()).map(|(idx, p)| Sprite {
+            mesh: MeshId::Ship(game_state.config.ships[idx]),
+            position: [p.vehicle.position_dx, p.vehicle.position_dy],
+            rotation: p.vehicle.vifo_theta.0,
+            owner: idx,
+        });
+        let torps = game_state.torps_in_flight.iter().map(|t| Sprite {
+            mesh: MeshId::Torpedo,
+            position: [t.vehicle.position_dx, t.vehicle.position_dy],
+            rotation: t.vehicle.vifo_theta.0,
+            owner: t.owner,
+        });
+
+        let trail_count = (TORPEDO_TRAIL_PER_SECOND * elapsed).round() as u32;
+        let trails = game_state.torps_in_flight.iter().map(|t| ParticleBurst {
+            emitter: TORPEDO_TRAIL,
+            count: trail_count,
+            position: [t.vehicle.position_dx, t.vehicle.position_dy],
+            velocity: [0.0, 0.0],
+            heading: 0.0,
+        });
+
+        Self {
+            sprites: ships.chain(torps).collect(),
+            bursts: events.iter().map(ParticleBurst::from_event).chain(trails).collect(),
+            gravity: GravityField::from_game_state(game_state),
+            elapsed,
+            seed: game_state.seed,
+            star_chart: StarChart::default(),
+            post: PostSettings::default(),
+            palette: Palette::default(),
+        }
+    }
+
+    // More or fewer particles in every burst, for slow GPUs or a busier screen.
+    pub fn scale_particles(&mut self, scale: f32) {
+        for burst in self.bursts.iter_mut() {
+            burst.count = (burst.count as f32 * scale).round() as u32;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::simulation::{create_game_state, MatchConfig, Torpedo, PLAYER_ONE};
+
+    const PARTICLE_FLOATS: usize = 10;
+
+    fn burst(emitter: Emitter, count: u32) -> ParticleBurst {
+        ParticleBurst { emitter, count, position: [0.2, -0.3], velocity: [0.05, 0.1], heading: 1.0 }
+    }
+
+    #[test]
+    fn bursts_lay_out_every_particle_within_the_emitters_limits() {
+        let burst = burst(EXHAUST, 50);
+        let mut particles = vec![];
+        burst.emit(&mut Rng::new(1), &mut particles);
+        assert_eq!(particles.len(), 50 * PARTICLE_FLOATS);
+
+        for particle in particles.chunks(PARTICLE_FLOATS) {
+            assert_eq!(particle[0..2], burst.position);
+            let (dx, dy) = (particle[2] - burst.velocity[0], particle[3] - burst.velocity[1]);
+            assert!((dx * dx + dy * dy).sqrt() <= EXHAUST.speed + 1e-6);
+            if dx != 0.0 || dy != 0.0 {
+                let off_heading = (dy.atan2(dx) - burst.heading).abs();
+                assert!(off_heading <= EXHAUST.spread + 1e-5, "{} off the heading", off_heading);
+            }
+            // born now, to die in half to all of the emitter's lifetime
+            assert_eq!(particle[4], 0.0);
+            assert!((EXHAUST.lifetime * 0.5..=EXHAUST.lifetime).contains(&particle[5]));
+            assert_eq!(particle[6..10], EXHAUST.color);
+        }
+    }
+
+    #[test]
+    fn the_same_rng_sprays_the_same_way() {
+        let emitted = |seed: u64| {
+            let mut particles = vec![];
+            burst(EXPLOSION, 20).emit(&mut Rng::new(seed), &mut particles);
+            particles
+        };
+        assert_eq!(emitted(3), emitted(3));
+        assert_ne!(emitted(3), emitted(4));
+    }
+
+    #[test]
+    fn torpedo_trails_grow_with_the_time_elapsed() {
+        let mut game_state = create_game_state(1, MatchConfig::default());
+        let vehicle = game_state.players[PLAYER_ONE].vehicle;
+        game_state.torps_in_flight.push(Torpedo { vehicle, owner: PLAYER_ONE, age: 0 });
+        let trail = |elapsed: f32| {
+            let frame = RenderFrame::from_game_state(&game_state, &[], elapsed);
+            let trails: Vec<&ParticleBurst> = frame.bursts.iter().filter(|b| b.emitter == TORPEDO_TRAIL).collect();
+            assert_eq!(trails.len(), 1);
+            trails[0].count
+        };
+        assert_eq!(trail(0.1), 9);
+        assert_eq!(trail(0.2), 18);
+        // paused
+        assert_eq!(trail(0.0), 0);
+    }
+
+    #[test]
+    fn exhaust_leaves_the_tail_and_heads_away_from_the_nose() {
+        let event = SimEvent::Thrust {
+            player: PLAYER_ONE,
+            position_dx: 0.5,
+            position_dy: 0.5,
+            velocity_dx: 0.1,
+            velocity_dy: 0.0,
+            vifo_theta: 0.0,
+        };
+        let burst = ParticleBurst::from_event(&event);
+        assert_eq!(burst.emitter, EXHAUST);
+        assert!((burst.position[0] - (0.5 - EXHAUST_OFFSET)).abs() < 1e-6);
+        assert!((burst.position[1] - 0.5).abs() < 1e-6);
+        assert_eq!(burst.velocity, [0.1, 0.0]);
+        assert_eq!(burst.heading, PI);
+    }
+}
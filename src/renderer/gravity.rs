@@ -0,0 +1,182 @@
+// Point masses that pull on particles, packed for the `Gravity` uniform block:
+//
+//     #define MAX_ATTRACTORS 64
+//     layout(std140) uniform Gravity {
+//         int u_attractor_count;
+//         vec4 u_attractors[MAX_ATTRACTORS];   // xyz position, w mass
+//     };
+//
+// Under std140 the int takes the first 4 bytes and the array, having vec4 alignment,
+// starts at byte 16, with a 16 byte stride.  The block is always uploaded at its full
+// size; only the first `u_attractor_count` entries are read.
+
+use web_sys::{WebGl2RenderingContext as GL, WebGlBuffer};
+
+use super::errors::RendererError;
+use super::shaders::GRAVITY_BINDING;
+use crate::simulation::GameState;
+
+// keep in step with MAX_ATTRACTORS in particles.vert and effects.vert
+pub const MAX_ATTRACTORS: usize = 64;
+
+const HEADER_BYTES: usize = 16;
+const ATTRACTOR_BYTES: usize = 16;
+pub const GRAVITY_BLOCK_BYTES: usize = HEADER_BYTES + MAX_ATTRACTORS * ATTRACTOR_BYTES;
+
+const SHIP_MASS: f32 = 0.004;
+const TORPEDO_MASS: f32 = 0.001;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Attractor {
+    pub position: [f32; 3],
+    pub mass: f32,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct GravityField {
+    pub attractors: Vec<Attractor>,
+}
+
+impl GravityField {
+    // Every live ship and torpedo pulls a little.  A star, when the rules have one, pulls
+    // the particles just as the simulation has it pull the ships, since effects.vert
+    // falls off with the square of the distance too.  It goes first, so it's never the
+    // one dropped from a full block.
+    pub fn from_game_state(game_state: &GameState) -> Self {
+        let gravity = game_state.config.rules.gravity;
+        let star = (gravity.strength > 0.0)
+            .then_some(Attractor { position: [0.0, 0.0, 0.0], mass: gravity.strength });
+        let ships = game_state.players.iter().filter(|p| p.present()).map(|p| Attractor {
+            position: [p.vehicle.position_dx, p.vehicle.position_dy, 0.0],
+            mass: SHIP_MASS,
+        });
+        let torps = game_state.torps_in_flight.iter().map(|t| Attractor {
+            position: [t.vehicle.position_dx, t.vehicle.position_dy, 0.0],
+            mass: TORPEDO_MASS,
+        });
+        Self { attractors: star.into_iter().chain(ships).chain(torps).collect() }
+    }
+
+    // The whole block, std140.  Attractors past MAX_ATTRACTORS are dropped.
+    pub fn pack_std140(&self) -> Vec<u8> {
+        let mut block = vec![0u8; GRAVITY_BLOCK_BYTES];
+        let count = self.attractors.len().min(MAX_ATTRACTORS);
+        block[0..4].copy_from_slice(&(count as i32).to_le_bytes());
+
+        for (idx, attractor) in self.attractors.iter().take(count).enumerate() {
+            let [x, y, z] = attractor.position;
+            let start = HEADER_BYTES + idx * ATTRACTOR_BYTES;
+            for (offset, value) in [x, y, z, attractor.mass].iter().enumerate() {
+                let at = start + offset * 4;
+                block[at..at + 4].copy_from_slice(&value.to_le_bytes());
+            }
+        }
+        block
+    }
+}
+
+// The uniform buffer behind the block, bound at GRAVITY_BINDING for every program.
+pub struct GravityBuffer {
+    buffer: WebGlBuffer,
+}
+
+impl GravityBuffer {
+    pub fn new(gl: &GL) -> Result<Self, RendererError> {
+        let buffer = gl.create_buffer().ok_or("could not create buffer")?;
+        gl.bind_buffer(GL::UNIFORM_BUFFER, Some(&buffer));
+        gl.buffer_data_with_i32(GL::UNIFORM_BUFFER, GRAVITY_BLOCK_BYTES as i32, GL::DYNAMIC_DRAW);
+        gl.bind_buffer(GL::UNIFORM_BUFFER, None);
+        gl.bind_buffer_base(GL::UNIFORM_BUFFER, GRAVITY_BINDING, Some(&buffer));
+        Ok(Self { buffer })
+    }
+
+    pub fn upload(&self, gl: &GL, field: &GravityField) {
+        gl.bind_buffer(GL::UNIFORM_BUFFER, Some(&self.buffer));
+        gl.buffer_sub_data_with_i32_and_u8_array(GL::UNIFORM_BUFFER, 0, &field.pack_std140());
+        gl.bind_buffer(GL::UNIFORM_BUFFER, None);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::simulation::{create_game_state, GameRules, MatchConfig, PLAYER_TWO};
+
+    fn int_at(block: &[u8], at: usize) -> i32 {
+        i32::from_le_bytes(block[at..at + 4].try_into().unwrap())
+    }
+
+    fn float_at(block: &[u8], at: usize) -> f32 {
+        f32::from_le_bytes(block[at..at + 4].try_into().unwrap())
+    }
+
+    #[test]
+    fn empty_field_is_a_zeroed_full_block() {
+        let block = GravityField::default().pack_std140();
+        assert_eq!(block.len(), GRAVITY_BLOCK_BYTES);
+        assert!(block.iter().all(|b| *b == 0));
+    }
+
+    #[test]
+    fn attractors_follow_the_padded_count() {
+        let field = GravityField {
+            attractors: vec![
+                Attractor { position: [0.1, 0.2, 0.3], mass: 0.5 },
+                Attractor { position: [-0.4, -0.5, 0.0], mass: 2.0 },
+            ],
+        };
+        let block = field.pack_std140();
+
+        assert_eq!(int_at(&block, 0), 2);
+        // padding after the count
+        assert!(block[4..16].iter().all(|b| *b == 0));
+
+        assert_eq!(float_at(&block, 16), 0.1);
+        assert_eq!(float_at(&block, 20), 0.2);
+        assert_eq!(float_at(&block, 24), 0.3);
+        assert_eq!(float_at(&block, 28), 0.5);
+
+        assert_eq!(float_at(&block, 32), -0.4);
+        assert_eq!(float_at(&block, 36), -0.5);
+        assert_eq!(float_at(&block, 40), 0.0);
+        assert_eq!(float_at(&block, 44), 2.0);
+
+        // unused slots
+        assert!(block[48..].iter().all(|b| *b == 0));
+    }
+
+    #[test]
+    fn too_many_attractors_are_dropped() {
+        let attractor = Attractor { position: [0.0, 0.0, 0.0], mass: 1.0 };
+        let field = GravityField { attractors: vec![attractor; MAX_ATTRACTORS + 10] };
+        let block = field.pack_std140();
+
+        assert_eq!(block.len(), GRAVITY_BLOCK_BYTES);
+        assert_eq!(int_at(&block, 0), MAX_ATTRACTORS as i32);
+        let last = HEADER_BYTES + (MAX_ATTRACTORS - 1) * ATTRACTOR_BYTES;
+        assert_eq!(float_at(&block, last + 12), 1.0);
+    }
+
+    #[test]
+    fn live_ships_and_torpedoes_attract() {
+        let mut game_state = create_game_state(7, MatchConfig::default());
+        assert_eq!(GravityField::from_game_state(&game_state).attractors.len(), 2);
+
+        game_state.players[PLAYER_TWO].alive = false;
+        let field = GravityField::from_game_state(&game_state);
+        assert_eq!(field.attractors.len(), 1);
+        assert_eq!(field.attractors[0].position, [0.3, 0.3, 0.0]);
+        assert_eq!(field.attractors[0].mass, SHIP_MASS);
+    }
+
+    #[test]
+    fn a_star_pulls_from_the_centre_as_hard_as_the_rules_say() {
+        let star = |rules: &&GameRules| rules.gravity.strength > 0.0;
+        let rules = crate::simulation::presets().iter().find(star).unwrap();
+        let config = MatchConfig { rules: rules.clone(), ..MatchConfig::default() };
+        let field = GravityField::from_game_state(&create_game_state(7, config));
+        assert_eq!(field.attractors.len(), 3);
+        assert_eq!(field.attractors[0], Attractor { position: [0.0, 0.0, 0.0], mass: rules.gravity.strength });
+        assert!(field.attractors[1..].iter().all(|a| a.mass < rules.gravity.strength));
+    }
+}
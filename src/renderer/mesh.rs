@@ -0,0 +1,63 @@
+use web_sys::{WebGl2RenderingContext as GL, WebGlBuffer, WebGlVertexArrayObject};
+
+// Attribute locations, matching the layout qualifiers in sprite.vert.  Position is per
+// vertex; the rest advance once per instance.
+pub const POSITION_ATTRIBUTE: u32 = 0;
+pub const INSTANCE_POSITION_ATTRIBUTE: u32 = 1;
+pub const INSTANCE_ROTATION_ATTRIBUTE: u32 = 2;
+pub const INSTANCE_COLOR_ATTRIBUTE: u32 = 3;
+
+// x, y, rotation, r, g, b, a
+pub const INSTANCE_FLOATS: usize = 7;
+
+pub const TORPEDO_100_VERTICES: [f32; 6] = [
+    0.007, 0.0,
+    -0.0038, -0.0038,
+    -0.0038, 0.0038,
+];
+
+// A vertex buffer uploaded once, an instance buffer refilled every frame, and the
+// attribute layout for both captured in a VAO (which keeps the buffers alive).  Any
+// program with sprite.vert's inputs can draw it.
+pub struct Mesh {
+    pub vao: WebGlVertexArrayObject,
+    pub instance_buffer: WebGlBuffer,
+    pub vertex_count: i32,
+}
+
+impl Mesh {
+    // `vertices` are 2d triangles, x and y interleaved.
+    pub fn new(gl: &GL, vertices: &[f32]) -> Result<Self, String> {
+        let vao = gl.create_vertex_array().ok_or("could not create vertex array")?;
+        gl.bind_vertex_array(Some(&vao));
+
+        let vertex_buffer = gl.create_buffer().ok_or("could not create buffer")?;
+        gl.bind_buffer(GL::ARRAY_BUFFER, Some(&vertex_buffer));
+        let js_vertices = js_sys::Float32Array::from(vertices);
+        gl.buffer_data_with_array_buffer_view(GL::ARRAY_BUFFER, &js_vertices, GL::STATIC_DRAW);
+        gl.vertex_attrib_pointer_with_i32(POSITION_ATTRIBUTE, 2, GL::FLOAT, false, 0, 0);
+        gl.enable_vertex_attrib_array(POSITION_ATTRIBUTE);
+
+        let instance_buffer = gl.create_buffer().ok_or("could not create buffer")?;
+        gl.bind_buffer(GL::ARRAY_BUFFER, Some(&instance_buffer));
+        let stride = (INSTANCE_FLOATS * 4) as i32;
+        for (attribute, size, offset) in [
+            (INSTANCE_POSITION_ATTRIBUTE, 2, 0),
+            (INSTANCE_ROTATION_ATTRIBUTE, 1, 2),
+            (INSTANCE_COLOR_ATTRIBUTE, 4, 3),
+        ] {
+            gl.vertex_attrib_pointer_with_i32(attribute, size, GL::FLOAT, false, stride, offset * 4);
+            gl.vertex_attrib_divisor(attribute, 1);
+            gl.enable_vertex_attrib_array(attribute);
+        }
+
+        gl.bind_vertex_array(None);
+        gl.bind_buffer(GL::ARRAY_BUFFER, None);
+
+        Ok(Self {
+            vao,
+            instance_buffer,
+            vertex_count: (vertices.len() / 2) as i32,
+        })
+    }
+}
@@ -0,0 +1,354 @@
+// All drawing for the game goes through `Renderer`.  It keeps track of the canvas size,
+// batches sprites into one instanced draw per mesh, and turns whatever a `RenderFrame`
+// describes into calls on a `RenderBackend`, through the post effects in post.rs when any
+// are on.  The WebGL backend owns the context and every GL handle; callers never touch
+// them.
+
+pub mod backend;
+pub mod context;
+pub mod effects;
+pub mod errors;
+pub mod frame;
+pub mod gravity;
+pub mod mesh;
+pub mod particle_system;
+pub mod post;
+#[cfg(test)]
+pub mod recording;
+pub mod shaders;
+pub mod starfield;
+pub mod viewport;
+pub mod webgl;
+
+use cgmath::{Matrix4, SquareMatrix};
+use web_sys::HtmlCanvasElement;
+
+use backend::{ContextStatus, DrawCall, RenderBackend};
+pub use errors::{report, RendererError};
+pub use frame::{MeshId, Palette, RenderFrame};
+pub use post::{PostQuality, PostSettings};
+use shaders::{SPRITE_FRAG, SPRITE_VERT};
+pub use starfield::StarChart;
+pub use viewport::{ARENA_OVERLAY_STYLE, CANVAS_BOX_STYLE, CANVAS_STYLE};
+use viewport::Viewport;
+use webgl::WebGlBackend;
+
+// under the nebula, which covers the whole arena
+const ARENA_COLOR: [f32; 4] = [0.0, 0.0, 0.02, 1.0];
+const LETTERBOX_COLOR: [f32; 4] = [0.12, 0.12, 0.12, 1.0];
+// particle diameter, as a fraction of the arena's side
+const PARTICLE_SIZE: f32 = 0.003;
+
+pub struct Renderer<B: RenderBackend = WebGlBackend> {
+    backend: B,
+    viewport: Option<Viewport>,
+    // what the backend's camera holds
+    projection: Option<Matrix4<f32>>,
+    // set when the offscreen targets couldn't be made, after which frames skip the post
+    // effects rather than report it again every frame
+    post_failed: bool,
+    // seconds of game time drawn so far, for the twinkle
+    clock: f32,
+}
+
+impl Renderer<WebGlBackend> {
+    pub fn from_canvas(canvas: &HtmlCanvasElement) -> Result<Self, RendererError> {
+        Ok(Self::new(WebGlBackend::from_canvas(canvas)?))
+    }
+}
+
+impl<B: RenderBackend> Renderer<B> {
+    pub fn new(backend: B) -> Self {
+        Self { backend, viewport: None, projection: None, post_failed: false, clock: 0.0 }
+    }
+
+    // Whether anything drawn now would show.  It isn't while the WebGL context is lost,
+    // and callers hold the game still until it's back.
+    pub fn ready(&mut self) -> bool {
+        match self.backend.context_status() {
+            ContextStatus::Ready => true,
+            ContextStatus::Lost => false,
+            ContextStatus::Restored => {
+                // the backend starts over, so send everything again
+                self.projection = None;
+                self.post_failed = false;
+                true
+            }
+        }
+    }
+
+    pub fn draw(&mut self, frame: &RenderFrame) {
+        if !self.ready() {
+            return;
+        }
+        if let Some(viewport) = self.backend.take_resize() {
+            self.viewport = Some(viewport);
+        }
+        let viewport = match self.viewport {
+            Some(viewport) => viewport,
+            None => return,
+        };
+        self.clock += frame.elapsed;
+        let [x, y, arena_side] = viewport.arena;
+
+        let offscreen = frame.post.enabled()
+            && !self.post_failed
+            && match self.backend.bind_scene(arena_side, frame.post.quality) {
+                Ok(()) => true,
+                Err(e) => {
+                    report(&e);
+                    self.post_failed = true;
+                    false
+                }
+            };
+
+        if offscreen {
+            // the scene is just the arena, so it needs no letterboxing
+            let side = frame.post.quality.scene_side(arena_side);
+            self.backend.set_viewport([0, 0, side, side]);
+            self.backend.set_scissor(None);
+            self.backend.clear(ARENA_COLOR);
+            self.set_projection(Matrix4::identity());
+            self.draw_scene(frame, side as f32);
+            self.backend.draw(DrawCall::PostProcess { settings: frame.post, elapsed: frame.elapsed });
+
+            self.backend.bind_canvas();
+            self.clear(&viewport);
+            self.backend.set_viewport([x, y, arena_side, arena_side]);
+            self.backend.draw(DrawCall::Composite { settings: frame.post, arena_side });
+        } else {
+            self.backend.bind_canvas();
+            self.clear(&viewport);
+            self.set_projection(viewport.projection());
+            self.draw_scene(frame, arena_side as f32);
+        }
+    }
+
+    // The sky, the effects, then one instanced draw per mesh, however many sprites use
+    // it.  `side` is the arena's side in pixels of whatever is being drawn to.
+    fn draw_scene(&mut self, frame: &RenderFrame, side: f32) {
+        self.backend.draw(DrawCall::Sky {
+            seed: frame.seed,
+            chart: frame.star_chart,
+            time: self.clock,
+            point_scale: side,
+        });
+        // behind the ships
+        self.backend.draw(DrawCall::Particles {
+            bursts: frame.bursts.clone(),
+            gravity: frame.gravity.clone(),
+            delta: frame.elapsed,
+            point_size: side * PARTICLE_SIZE,
+        });
+
+        let colors = frame.palette.colors();
+        for mesh in MeshId::ALL {
+            let mut instances: Vec<f32> = vec![];
+            for sprite in frame.sprites.iter().filter(|s| s.mesh == mesh) {
+                instances.extend_from_slice(&[sprite.position[0], sprite.position[1], sprite.rotation]);
+                instances.extend_from_slice(&colors[sprite.owner]);
+            }
+            if !instances.is_empty() {
+                self.backend.draw(DrawCall::Sprites { vert: SPRITE_VERT, frag: SPRITE_FRAG, mesh, instances });
+            }
+        }
+    }
+
+    // Hands `projection` to the backend unless it already has it.
+    fn set_projection(&mut self, projection: Matrix4<f32>) {
+        if self.projection.replace(projection) != Some(projection) {
+            self.backend.set_projection(projection);
+        }
+    }
+
+    // Letterbox the whole canvas, then clear the arena and leave the scissor on it so
+    // nothing (ships wrapping at the edges, say) draws over the bars.
+    fn clear(&mut self, viewport: &Viewport) {
+        let [x, y, side] = viewport.arena;
+        self.backend.set_viewport([0, 0, viewport.width as i32, viewport.height as i32]);
+        self.backend.set_scissor(None);
+        self.backend.clear(LETTERBOX_COLOR);
+        self.backend.set_scissor(Some([x, y, side, side]));
+        self.backend.clear(ARENA_COLOR);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mesh::INSTANCE_FLOATS;
+    use crate::simulation::{create_game_state, MatchConfig, ShipDesign, Torpedo, PLAYER_ONE, PLAYER_TWO};
+    use recording::{Command, RecordingBackend};
+
+    // 800 x 600, so the arena is 600 square with 100 either side
+    fn renderer() -> Renderer<RecordingBackend> {
+        Renderer::new(RecordingBackend::new(Viewport::fit(800.0, 600.0, 1.0)))
+    }
+
+    fn plain(mut frame: RenderFrame) -> RenderFrame {
+        frame.post = PostSettings { persistence: false, bloom: false, scanlines: false, curvature: false, ..frame.post };
+        frame
+    }
+
+    fn sprites(backend: &RecordingBackend) -> Vec<(MeshId, Vec<[f32; 7]>)> {
+        backend
+            .draws()
+            .filter_map(|call| match call {
+                DrawCall::Sprites { vert, frag, mesh, instances } => {
+                    assert_eq!((*vert, *frag), (SPRITE_VERT, SPRITE_FRAG));
+                    let instances = instances.chunks(INSTANCE_FLOATS).map(|i| i.try_into().unwrap()).collect();
+                    Some((*mesh, instances))
+                }
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn ships_and_torpedoes_are_drawn_where_they_are_in_their_owners_colours() {
+        let config = MatchConfig { ships: [ShipDesign::Wedge, ShipDesign::Needle], ..MatchConfig::default() };
+        let mut game_state = create_game_state(1, config);
+        for (idx, owner) in [PLAYER_ONE, PLAYER_TWO, PLAYER_TWO].into_iter().enumerate() {
+            let mut vehicle = game_state.players[owner].vehicle;
+            vehicle.position_dx = 0.1 * idx as f32;
+            game_state.torps_in_flight.push(Torpedo { vehicle, owner, age: 0 });
+        }
+
+        let mut renderer = renderer();
+        let mut frame = RenderFrame::from_game_state(&game_state, &[], 0.016);
+        frame.palette = Palette::Phosphor;
+        renderer.draw(&frame);
+
+        let colors = Palette::Phosphor.colors();
+        let instance = |vehicle: &crate::simulation::vehicle::Vehicle_100, color: [f32; 4]| {
+            let [r, g, b, a] = color;
+            [vehicle.position_dx, vehicle.position_dy, vehicle.vifo_theta.0, r, g, b, a]
+        };
+        let ships = &game_state.players;
+        let torps = &game_state.torps_in_flight;
+        // torpedoes first, under the ships, then a draw per design in use
+        assert_eq!(
+            sprites(&renderer.backend),
+            vec![
+                (
+                    MeshId::Torpedo,
+                    torps.iter().map(|t| instance(&t.vehicle, colors[t.owner])).collect(),
+                ),
+                (MeshId::Ship(ShipDesign::Needle), vec![instance(&ships[PLAYER_TWO].vehicle, colors[PLAYER_TWO])]),
+                (MeshId::Ship(ShipDesign::Wedge), vec![instance(&ships[PLAYER_ONE].vehicle, colors[PLAYER_ONE])]),
+            ]
+        );
+    }
+
+    #[test]
+    fn dead_ships_are_not_drawn() {
+        let mut game_state = create_game_state(1, MatchConfig::default());
+        game_state.players[PLAYER_ONE].alive = false;
+        let mut renderer = renderer();
+        renderer.draw(&RenderFrame::from_game_state(&game_state, &[], 0.016));
+        let drawn = sprites(&renderer.backend);
+        assert_eq!(drawn.len(), 1);
+        assert_eq!(drawn[0].0, MeshId::Ship(game_state.config.ships[PLAYER_TWO]));
+        assert_eq!(drawn[0].1.len(), 1);
+    }
+
+    #[test]
+    fn without_post_effects_the_scene_goes_straight_to_the_letterboxed_canvas() {
+        let game_state = create_game_state(1, MatchConfig::default());
+        let mut renderer = renderer();
+        let frame = plain(RenderFrame::from_game_state(&game_state, &[], 0.016));
+        renderer.draw(&frame);
+
+        let viewport = Viewport::fit(800.0, 600.0, 1.0);
+        assert_eq!(
+            renderer.backend.commands[..7],
+            [
+                Command::BindCanvas,
+                Command::Viewport([0, 0, 800, 600]),
+                Command::Scissor(None),
+                Command::Clear(LETTERBOX_COLOR),
+                Command::Scissor(Some([100, 0, 600, 600])),
+                Command::Clear(ARENA_COLOR),
+                Command::Projection(viewport.projection()),
+            ]
+        );
+        assert_eq!(
+            renderer.backend.commands[7],
+            Command::Draw(DrawCall::Sky { seed: 1, chart: StarChart::Procedural, time: 0.016, point_scale: 600.0 })
+        );
+        assert!(!renderer.backend.draws().any(|call| matches!(call, DrawCall::PostProcess { .. })));
+    }
+
+    #[test]
+    fn post_effects_draw_the_scene_offscreen_then_onto_the_arena() {
+        let game_state = create_game_state(1, MatchConfig::default());
+        let mut renderer = renderer();
+        let mut frame = RenderFrame::from_game_state(&game_state, &[], 0.016);
+        frame.post.quality = PostQuality::Low;
+        renderer.draw(&frame);
+
+        let commands = &renderer.backend.commands;
+        assert_eq!(
+            commands[..5],
+            [
+                Command::BindScene { arena_side: 600, quality: PostQuality::Low },
+                Command::Viewport([0, 0, 300, 300]),
+                Command::Scissor(None),
+                Command::Clear(ARENA_COLOR),
+                Command::Projection(Matrix4::identity()),
+            ]
+        );
+        assert!(commands.contains(&Command::Draw(DrawCall::Sky {
+            seed: 1,
+            chart: StarChart::Procedural,
+            time: 0.016,
+            point_scale: 300.0,
+        })));
+        let n = commands.len();
+        assert_eq!(commands[n - 1], Command::Draw(DrawCall::Composite { settings: frame.post, arena_side: 600 }));
+        assert_eq!(commands[n - 2], Command::Viewport([100, 0, 600, 600]));
+        let processed = commands.iter().position(|c| matches!(c, Command::Draw(DrawCall::PostProcess { .. })));
+        let canvas = commands.iter().position(|c| *c == Command::BindCanvas);
+        assert!(processed.unwrap() < canvas.unwrap());
+    }
+
+    #[test]
+    fn nothing_is_drawn_while_the_context_is_lost_and_it_all_goes_again_after() {
+        let game_state = create_game_state(1, MatchConfig::default());
+        let mut renderer = renderer();
+        // no time passes, so the twinkle matches too
+        let frame = plain(RenderFrame::from_game_state(&game_state, &[], 0.0));
+        renderer.draw(&frame);
+        let drawn = renderer.backend.commands.len();
+
+        renderer.backend.status = ContextStatus::Lost;
+        assert!(!renderer.ready());
+        renderer.draw(&frame);
+        assert_eq!(renderer.backend.commands.len(), drawn);
+
+        renderer.backend.status = ContextStatus::Restored;
+        renderer.draw(&frame);
+        assert!(renderer.ready());
+        // the same frame again, projection and all
+        assert_eq!(renderer.backend.commands[drawn..], renderer.backend.commands[..drawn]);
+    }
+
+    #[test]
+    fn projection_is_sent_only_when_it_changes() {
+        let game_state = create_game_state(1, MatchConfig::default());
+        let mut renderer = renderer();
+        let frame = plain(RenderFrame::from_game_state(&game_state, &[], 0.016));
+        renderer.draw(&frame);
+        renderer.draw(&frame);
+        renderer.backend.resize = Some(Viewport::fit(600.0, 800.0, 1.0));
+        renderer.draw(&frame);
+
+        let projections: Vec<_> = renderer
+            .backend
+            .commands
+            .iter()
+            .filter(|c| matches!(c, Command::Projection(_)))
+            .collect();
+        assert_eq!(projections.len(), 2);
+    }
+}
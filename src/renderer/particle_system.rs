@@ -0,0 +1,152 @@
+// Ping-pong transform feedback particles.
+//
+// Particle state lives in two buffers, A and B, each with a VAO that reads it and a
+// transform feedback object that writes it.  `step` draws from one side while the vertex
+// shader's outputs are captured into the other, then swaps, so the next step reads what
+// this one wrote.  (The game_* components reassigned their "current" VAO and feedback in
+// locals that were thrown away at the end of the frame, so the sides never swapped.)
+//
+// The state is interleaved, one attribute after another in `layout` order, and the
+// program drawing it must be linked with INTERLEAVED_ATTRIBS and its varyings in that
+// same order.  Attributes that never change (a colour per particle, say) can sit in a
+// separate static buffer shared by both sides.
+
+use std::cell::{Cell, RefCell};
+
+use web_sys::{
+    WebGl2RenderingContext as GL, WebGlBuffer, WebGlTransformFeedback, WebGlVertexArrayObject,
+};
+
+use super::errors::RendererError;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ParticleAttribute {
+    pub location: u32,
+    // in floats
+    pub size: i32,
+}
+
+struct Side {
+    buffer: WebGlBuffer,
+    vao: WebGlVertexArrayObject,
+    feedback: WebGlTransformFeedback,
+}
+
+pub struct GpuParticleSystem {
+    capacity: usize,
+    floats: usize,
+    sides: [Side; 2],
+    // the side drawn next
+    current: Cell<usize>,
+    statics: RefCell<Vec<WebGlBuffer>>,
+}
+
+impl GpuParticleSystem {
+    // `capacity` zeroed particles.
+    pub fn new(gl: &GL, capacity: usize, layout: &[ParticleAttribute]) -> Result<Self, RendererError> {
+        let floats = layout.iter().map(|a| a.size as usize).sum();
+        Ok(Self {
+            capacity,
+            floats,
+            sides: [
+                Self::side(gl, capacity * floats, layout)?,
+                Self::side(gl, capacity * floats, layout)?,
+            ],
+            current: Cell::new(0),
+            statics: RefCell::new(vec![]),
+        })
+    }
+
+    fn side(gl: &GL, len: usize, layout: &[ParticleAttribute]) -> Result<Side, RendererError> {
+        let buffer = gl.create_buffer().ok_or("could not create buffer")?;
+        gl.bind_buffer(GL::ARRAY_BUFFER, Some(&buffer));
+        gl.buffer_data_with_i32(GL::ARRAY_BUFFER, (len * 4) as i32, GL::STREAM_COPY);
+
+        let vao = gl.create_vertex_array().ok_or("could not create vertex array")?;
+        gl.bind_vertex_array(Some(&vao));
+        bind_layout(gl, layout);
+        gl.bind_vertex_array(None);
+        gl.bind_buffer(GL::ARRAY_BUFFER, None);
+
+        let feedback = gl.create_transform_feedback().ok_or("could not create transform feedback")?;
+        gl.bind_transform_feedback(GL::TRANSFORM_FEEDBACK, Some(&feedback));
+        gl.bind_buffer_base(GL::TRANSFORM_FEEDBACK_BUFFER, 0, Some(&buffer));
+        gl.bind_transform_feedback(GL::TRANSFORM_FEEDBACK, None);
+        gl.bind_buffer_base(GL::TRANSFORM_FEEDBACK_BUFFER, 0, None);
+
+        Ok(Side { buffer, vao, feedback })
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    // Floats per particle in the state buffers.
+    pub fn stride(&self) -> usize {
+        self.floats
+    }
+
+    // Overwrites particles from slot `first` on, in the side the next step reads.
+    pub fn write(&self, gl: &GL, first: usize, particles: &[f32]) {
+        debug_assert!(particles.len().is_multiple_of(self.floats));
+        debug_assert!(first * self.floats + particles.len() <= self.capacity * self.floats);
+        let js_particles = js_sys::Float32Array::from(particles);
+        gl.bind_buffer(GL::ARRAY_BUFFER, Some(&self.sides[self.current.get()].buffer));
+        gl.buffer_sub_data_with_i32_and_array_buffer_view(
+            GL::ARRAY_BUFFER,
+            (first * self.floats * 4) as i32,
+            &js_particles,
+        );
+        gl.bind_buffer(GL::ARRAY_BUFFER, None);
+    }
+
+    // Per-particle attributes that transform feedback leaves alone, interleaved like the
+    // state and read by both sides.
+    pub fn add_static_attributes(
+        &self,
+        gl: &GL,
+        layout: &[ParticleAttribute],
+        data: &[f32],
+    ) -> Result<(), RendererError> {
+        let buffer = gl.create_buffer().ok_or("could not create buffer")?;
+        gl.bind_buffer(GL::ARRAY_BUFFER, Some(&buffer));
+        let js_data = js_sys::Float32Array::from(data);
+        gl.buffer_data_with_array_buffer_view(GL::ARRAY_BUFFER, &js_data, GL::STATIC_DRAW);
+        for side in self.sides.iter() {
+            gl.bind_vertex_array(Some(&side.vao));
+            bind_layout(gl, layout);
+        }
+        gl.bind_vertex_array(None);
+        gl.bind_buffer(GL::ARRAY_BUFFER, None);
+        self.statics.borrow_mut().push(buffer);
+        Ok(())
+    }
+
+    // Draws every particle as a point while capturing the program's outputs as the new
+    // state.  The caller has the program in use and its uniforms set.
+    pub fn step(&self, gl: &GL) {
+        let current = self.current.get();
+        let (source, target) = (&self.sides[current], &self.sides[1 - current]);
+
+        gl.bind_vertex_array(Some(&source.vao));
+        gl.bind_transform_feedback(GL::TRANSFORM_FEEDBACK, Some(&target.feedback));
+        gl.begin_transform_feedback(GL::POINTS);
+        gl.draw_arrays(GL::POINTS, 0, self.capacity as i32);
+        gl.end_transform_feedback();
+        gl.bind_transform_feedback(GL::TRANSFORM_FEEDBACK, None);
+        gl.bind_vertex_array(None);
+
+        self.current.set(1 - current);
+    }
+}
+
+// Points the layout's attributes at the bound ARRAY_BUFFER, interleaved.
+fn bind_layout(gl: &GL, layout: &[ParticleAttribute]) {
+    let stride: i32 = layout.iter().map(|a| a.size * 4).sum();
+    let mut offset = 0;
+    for attribute in layout {
+        gl.vertex_attrib_pointer_with_i32(attribute.location, attribute.size, GL::FLOAT, false, stride, offset);
+        gl.enable_vertex_attrib_array(attribute.location);
+        offset += attribute.size * 4;
+    }
+}
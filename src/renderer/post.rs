@@ -0,0 +1,363 @@
+// The vector-display look: the scene is drawn into an offscreen texture the size of the
+// arena, then
+//
+//   phosphor   blends it over what's left of the last frames, so bright things leave a
+//              fading trail like the PDP-1's long-persistence tube
+//   bloom      blurs the bright parts at a lower resolution, for the glow around them
+//   composite  puts both on the canvas through a slightly curved screen, with scanlines
+//
+// Each step can be turned off, and `PostQuality` trades resolution and blur passes for
+// speed on weak GPUs.  With everything off the renderer skips all of this and draws
+// straight to the canvas as before.
+
+use std::cell::{Cell, RefCell};
+
+use serde::{Deserialize, Serialize};
+use web_sys::{
+    WebGl2RenderingContext as GL, WebGlFramebuffer, WebGlProgram, WebGlTexture, WebGlUniformLocation,
+    WebGlVertexArrayObject,
+};
+
+use super::errors::RendererError;
+use super::shaders::{ProgramBuilder, BLUR_FRAG, COMPOSITE_FRAG, PHOSPHOR_FRAG, POST_VERT};
+
+// seconds for a trail to fade to about a third
+const PHOSPHOR_SECONDS: f32 = 0.12;
+// only what's brighter than this glows, so the nebula and faint stars stay put
+const BLOOM_THRESHOLD: f32 = 0.3;
+const BLOOM_STRENGTH: f32 = 1.4;
+const CURVATURE: f32 = 0.06;
+// device pixels per scanline
+const SCANLINE_PIXELS: f32 = 3.0;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PostQuality {
+    Low,
+    #[default]
+    Medium,
+    High,
+}
+
+impl PostQuality {
+    pub const ALL: [PostQuality; 3] = [PostQuality::Low, PostQuality::Medium, PostQuality::High];
+
+    // The offscreen scene's side in pixels, for an arena `arena_side` across.
+    pub fn scene_side(self, arena_side: i32) -> i32 {
+        match self {
+            PostQuality::Low => scaled(arena_side, 0.5),
+            PostQuality::Medium | PostQuality::High => arena_side,
+        }
+    }
+
+    fn bloom_side(self, arena_side: i32) -> i32 {
+        match self {
+            PostQuality::Low => scaled(arena_side, 0.125),
+            PostQuality::Medium => scaled(arena_side, 0.25),
+            PostQuality::High => scaled(arena_side, 0.5),
+        }
+    }
+
+    // each one a horizontal and a vertical blur
+    fn blur_passes(self) -> usize {
+        match self {
+            PostQuality::Low => 1,
+            PostQuality::Medium => 2,
+            PostQuality::High => 3,
+        }
+    }
+}
+
+fn scaled(side: i32, scale: f32) -> i32 {
+    ((side as f32 * scale).round() as i32).max(1)
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PostSettings {
+    pub persistence: bool,
+    pub bloom: bool,
+    pub scanlines: bool,
+    pub curvature: bool,
+    pub quality: PostQuality,
+}
+
+impl Default for PostSettings {
+    fn default() -> Self {
+        Self { persistence: true, bloom: true, scanlines: true, curvature: true, quality: PostQuality::default() }
+    }
+}
+
+impl PostSettings {
+    pub fn enabled(&self) -> bool {
+        self.persistence || self.bloom || self.scanlines || self.curvature
+    }
+}
+
+// A square texture and the framebuffer that draws into it.
+struct Target {
+    framebuffer: WebGlFramebuffer,
+    texture: WebGlTexture,
+    side: i32,
+}
+
+impl Target {
+    fn new(gl: &GL, side: i32) -> Result<Self, RendererError> {
+        let texture = gl.create_texture().ok_or("could not create texture")?;
+        gl.bind_texture(GL::TEXTURE_2D, Some(&texture));
+        gl.tex_image_2d_with_i32_and_i32_and_i32_and_format_and_type_and_opt_u8_array(
+            GL::TEXTURE_2D,
+            0,
+            GL::RGBA8 as i32,
+            side,
+            side,
+            0,
+            GL::RGBA,
+            GL::UNSIGNED_BYTE,
+            None,
+        )
+        .map_err(|e| format!("{:?}", e))?;
+        for (parameter, value) in [
+            (GL::TEXTURE_MIN_FILTER, GL::LINEAR),
+            (GL::TEXTURE_MAG_FILTER, GL::LINEAR),
+            (GL::TEXTURE_WRAP_S, GL::CLAMP_TO_EDGE),
+            (GL::TEXTURE_WRAP_T, GL::CLAMP_TO_EDGE),
+        ] {
+            gl.tex_parameteri(GL::TEXTURE_2D, parameter, value as i32);
+        }
+        gl.bind_texture(GL::TEXTURE_2D, None);
+
+        let framebuffer = gl.create_framebuffer().ok_or("could not create framebuffer")?;
+        gl.bind_framebuffer(GL::FRAMEBUFFER, Some(&framebuffer));
+        gl.framebuffer_texture_2d(GL::FRAMEBUFFER, GL::COLOR_ATTACHMENT0, GL::TEXTURE_2D, Some(&texture), 0);
+        let status = gl.check_framebuffer_status(GL::FRAMEBUFFER);
+        gl.bind_framebuffer(GL::FRAMEBUFFER, None);
+        if status != GL::FRAMEBUFFER_COMPLETE {
+            return Err(RendererError::Gl(format!("offscreen framebuffer is incomplete ({:#x})", status)));
+        }
+
+        Ok(Self { framebuffer, texture, side })
+    }
+
+    fn bind(&self, gl: &GL) {
+        gl.bind_framebuffer(GL::FRAMEBUFFER, Some(&self.framebuffer));
+        gl.viewport(0, 0, self.side, self.side);
+    }
+
+    fn delete(&self, gl: &GL) {
+        gl.delete_framebuffer(Some(&self.framebuffer));
+        gl.delete_texture(Some(&self.texture));
+    }
+}
+
+// Sized for one arena and quality; replaced when either changes.
+struct Targets {
+    arena_side: i32,
+    quality: PostQuality,
+    scene: Target,
+    // the glow so far, ping-ponged like the particles
+    history: [Target; 2],
+    bloom: [Target; 2],
+}
+
+impl Targets {
+    fn new(gl: &GL, arena_side: i32, quality: PostQuality) -> Result<Self, RendererError> {
+        let scene_side = quality.scene_side(arena_side);
+        let bloom_side = quality.bloom_side(arena_side);
+        Ok(Self {
+            arena_side,
+            quality,
+            scene: Target::new(gl, scene_side)?,
+            history: [Target::new(gl, scene_side)?, Target::new(gl, scene_side)?],
+            bloom: [Target::new(gl, bloom_side)?, Target::new(gl, bloom_side)?],
+        })
+    }
+
+    fn delete(&self, gl: &GL) {
+        for target in [&self.scene].into_iter().chain(self.history.iter()).chain(self.bloom.iter()) {
+            target.delete(gl);
+        }
+    }
+}
+
+pub struct PostProcess {
+    quad: WebGlVertexArrayObject,
+    phosphor_program: WebGlProgram,
+    decay_loc: Option<WebGlUniformLocation>,
+    blur_program: WebGlProgram,
+    step_loc: Option<WebGlUniformLocation>,
+    threshold_loc: Option<WebGlUniformLocation>,
+    composite_program: WebGlProgram,
+    bloom_strength_loc: Option<WebGlUniformLocation>,
+    curvature_loc: Option<WebGlUniformLocation>,
+    scanlines_loc: Option<WebGlUniformLocation>,
+    targets: RefCell<Option<Targets>>,
+    // the history holding last frame's glow
+    current: Cell<usize>,
+    // whether last frame's glow is worth keeping
+    persisting: Cell<bool>,
+}
+
+impl PostProcess {
+    pub fn new(gl: &GL) -> Result<Self, RendererError> {
+        let quad = gl.create_vertex_array().ok_or("could not create vertex array")?;
+        let buffer = gl.create_buffer().ok_or("could not create buffer")?;
+        gl.bind_vertex_array(Some(&quad));
+        gl.bind_buffer(GL::ARRAY_BUFFER, Some(&buffer));
+        let js_quad = js_sys::Float32Array::from(&[-1.0, -1.0, 1.0, -1.0, -1.0, 1.0, 1.0, 1.0][..]);
+        gl.buffer_data_with_array_buffer_view(GL::ARRAY_BUFFER, &js_quad, GL::STATIC_DRAW);
+        gl.vertex_attrib_pointer_with_i32(0, 2, GL::FLOAT, false, 0, 0);
+        gl.enable_vertex_attrib_array(0);
+        gl.bind_vertex_array(None);
+        gl.bind_buffer(GL::ARRAY_BUFFER, None);
+
+        let phosphor_program = ProgramBuilder::new(gl, POST_VERT, PHOSPHOR_FRAG).build()?;
+        let blur_program = ProgramBuilder::new(gl, POST_VERT, BLUR_FRAG).build()?;
+        let composite_program = ProgramBuilder::new(gl, POST_VERT, COMPOSITE_FRAG).build()?;
+
+        // texture units never change, so set the samplers once
+        for (program, samplers) in [
+            (&phosphor_program, &["u_scene", "u_history"][..]),
+            (&blur_program, &["u_image"][..]),
+            (&composite_program, &["u_image", "u_bloom"][..]),
+        ] {
+            gl.use_program(Some(program));
+            for (unit, sampler) in samplers.iter().enumerate() {
+                gl.uniform1i(gl.get_uniform_location(program, sampler).as_ref(), unit as i32);
+            }
+        }
+        gl.use_program(None);
+
+        Ok(Self {
+            quad,
+            decay_loc: gl.get_uniform_location(&phosphor_program, "u_decay"),
+            step_loc: gl.get_uniform_location(&blur_program, "u_step"),
+            threshold_loc: gl.get_uniform_location(&blur_program, "u_threshold"),
+            bloom_strength_loc: gl.get_uniform_location(&composite_program, "u_bloom_strength"),
+            curvature_loc: gl.get_uniform_location(&composite_program, "u_curvature"),
+            scanlines_loc: gl.get_uniform_location(&composite_program, "u_scanlines"),
+            phosphor_program,
+            blur_program,
+            composite_program,
+            targets: RefCell::new(None),
+            current: Cell::new(0),
+            persisting: Cell::new(false),
+        })
+    }
+
+    // Points drawing at the offscreen scene, with the viewport on all of it, after sizing
+    // the targets for the arena and quality.
+    pub fn begin(&self, gl: &GL, arena_side: i32, quality: PostQuality) -> Result<(), RendererError> {
+        let mut targets = self.targets.borrow_mut();
+        if !matches!(&*targets, Some(t) if t.arena_side == arena_side && t.quality == quality) {
+            if let Some(old) = targets.take() {
+                old.delete(gl);
+            }
+            *targets = Some(Targets::new(gl, arena_side, quality)?);
+            // new textures start out black, so there's no glow to keep
+            self.persisting.set(false);
+        }
+        targets.as_ref().expect("targets were just made").scene.bind(gl);
+        Ok(())
+    }
+
+    // Phosphor and bloom on the scene just drawn, `elapsed` seconds after the last one,
+    // with the scissor off.  Leaves the canvas bound again.
+    pub fn process(&self, gl: &GL, settings: &PostSettings, elapsed: f32) {
+        let targets = self.targets.borrow();
+        let targets = match targets.as_ref() {
+            Some(targets) => targets,
+            None => return,
+        };
+        gl.disable(GL::BLEND);
+        gl.bind_vertex_array(Some(&self.quad));
+
+        if settings.persistence {
+            let current = self.current.get();
+            let (last, next) = (&targets.history[current], &targets.history[1 - current]);
+            // glow from before persistence was last turned off would flash back up
+            if !self.persisting.replace(true) {
+                last.bind(gl);
+                gl.clear_color(0.0, 0.0, 0.0, 1.0);
+                gl.clear(GL::COLOR_BUFFER_BIT);
+            }
+            next.bind(gl);
+            gl.use_program(Some(&self.phosphor_program));
+            gl.uniform1f(self.decay_loc.as_ref(), (-elapsed / PHOSPHOR_SECONDS).exp());
+            bind_textures(gl, &[&targets.scene.texture, &last.texture]);
+            gl.draw_arrays(GL::TRIANGLE_STRIP, 0, 4);
+            self.current.set(1 - current);
+        } else {
+            self.persisting.set(false);
+        }
+
+        if settings.bloom {
+            let [across, down] = &targets.bloom;
+            let texel = 1.0 / across.side as f32;
+            gl.use_program(Some(&self.blur_program));
+            for pass in 0..targets.quality.blur_passes() {
+                // the first pass picks the bright parts out of the full-size image
+                let (source, threshold) = match pass {
+                    0 => (self.image(settings, targets), BLOOM_THRESHOLD),
+                    _ => (&down.texture, 0.0),
+                };
+                across.bind(gl);
+                gl.uniform2f(self.step_loc.as_ref(), texel, 0.0);
+                gl.uniform1f(self.threshold_loc.as_ref(), threshold);
+                bind_textures(gl, &[source]);
+                gl.draw_arrays(GL::TRIANGLE_STRIP, 0, 4);
+
+                down.bind(gl);
+                gl.uniform2f(self.step_loc.as_ref(), 0.0, texel);
+                gl.uniform1f(self.threshold_loc.as_ref(), 0.0);
+                bind_textures(gl, &[&across.texture]);
+                gl.draw_arrays(GL::TRIANGLE_STRIP, 0, 4);
+            }
+        }
+
+        gl.bind_framebuffer(GL::FRAMEBUFFER, None);
+        gl.bind_vertex_array(None);
+        gl.enable(GL::BLEND);
+    }
+
+    // Draws the processed image over the viewport, which the caller has put on the
+    // arena, `arena_side` pixels across.
+    pub fn composite(&self, gl: &GL, settings: &PostSettings, arena_side: i32) {
+        let targets = self.targets.borrow();
+        let targets = match targets.as_ref() {
+            Some(targets) => targets,
+            None => return,
+        };
+        gl.disable(GL::BLEND);
+        gl.use_program(Some(&self.composite_program));
+        let bloom_strength = if settings.bloom { BLOOM_STRENGTH } else { 0.0 };
+        let curvature = if settings.curvature { CURVATURE } else { 0.0 };
+        let scanlines = if settings.scanlines { arena_side as f32 / SCANLINE_PIXELS } else { 0.0 };
+        gl.uniform1f(self.bloom_strength_loc.as_ref(), bloom_strength);
+        gl.uniform1f(self.curvature_loc.as_ref(), curvature);
+        gl.uniform1f(self.scanlines_loc.as_ref(), scanlines);
+        bind_textures(gl, &[self.image(settings, targets), &targets.bloom[1].texture]);
+        gl.bind_vertex_array(Some(&self.quad));
+        gl.draw_arrays(GL::TRIANGLE_STRIP, 0, 4);
+
+        gl.bind_vertex_array(None);
+        bind_textures(gl, &[]);
+        gl.enable(GL::BLEND);
+    }
+
+    // The scene with its trails, or without when persistence is off.
+    fn image<'a>(&self, settings: &PostSettings, targets: &'a Targets) -> &'a WebGlTexture {
+        match settings.persistence {
+            true => &targets.history[self.current.get()].texture,
+            false => &targets.scene.texture,
+        }
+    }
+}
+
+// Binds `textures` to units 0, 1, ... and unbinds the rest of the units used here.
+fn bind_textures(gl: &GL, textures: &[&WebGlTexture]) {
+    for unit in 0..2 {
+        gl.active_texture(GL::TEXTURE0 + unit as u32);
+        gl.bind_texture(GL::TEXTURE_2D, textures.get(unit).copied());
+    }
+    gl.active_texture(GL::TEXTURE0);
+}
@@ -0,0 +1,86 @@
+// A backend that draws nothing and writes down every call, for tests.
+
+use cgmath::Matrix4;
+
+use super::backend::{ContextStatus, DrawCall, RenderBackend};
+use super::errors::RendererError;
+use super::mesh::INSTANCE_FLOATS;
+use super::post::PostQuality;
+use super::viewport::Viewport;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Command {
+    BindCanvas,
+    BindScene { arena_side: i32, quality: PostQuality },
+    Viewport([i32; 4]),
+    Scissor(Option<[i32; 4]>),
+    Clear([f32; 4]),
+    Projection(Matrix4<f32>),
+    Draw(DrawCall),
+}
+
+pub struct RecordingBackend {
+    pub commands: Vec<Command>,
+    // handed to the renderer on its next frame, as if the canvas had been resized
+    pub resize: Option<Viewport>,
+    // what the renderer hears next time it asks; Restored is only said once
+    pub status: ContextStatus,
+}
+
+impl RecordingBackend {
+    pub fn new(viewport: Viewport) -> Self {
+        Self { commands: vec![], resize: Some(viewport), status: ContextStatus::Ready }
+    }
+
+    pub fn draws(&self) -> impl Iterator<Item = &DrawCall> {
+        self.commands.iter().filter_map(|command| match command {
+            Command::Draw(call) => Some(call),
+            _ => None,
+        })
+    }
+}
+
+impl RenderBackend for RecordingBackend {
+    fn take_resize(&mut self) -> Option<Viewport> {
+        self.resize.take()
+    }
+
+    fn context_status(&mut self) -> ContextStatus {
+        match self.status {
+            ContextStatus::Restored => std::mem::replace(&mut self.status, ContextStatus::Ready),
+            status => status,
+        }
+    }
+
+    fn bind_canvas(&mut self) {
+        self.commands.push(Command::BindCanvas);
+    }
+
+    fn bind_scene(&mut self, arena_side: i32, quality: PostQuality) -> Result<(), RendererError> {
+        self.commands.push(Command::BindScene { arena_side, quality });
+        Ok(())
+    }
+
+    fn set_viewport(&mut self, rect: [i32; 4]) {
+        self.commands.push(Command::Viewport(rect));
+    }
+
+    fn set_scissor(&mut self, rect: Option<[i32; 4]>) {
+        self.commands.push(Command::Scissor(rect));
+    }
+
+    fn clear(&mut self, color: [f32; 4]) {
+        self.commands.push(Command::Clear(color));
+    }
+
+    fn set_projection(&mut self, projection: Matrix4<f32>) {
+        self.commands.push(Command::Projection(projection));
+    }
+
+    fn draw(&mut self, call: DrawCall) {
+        if let DrawCall::Sprites { instances, .. } = &call {
+            assert!(instances.len().is_multiple_of(INSTANCE_FLOATS), "partial instance in {:?}", call);
+        }
+        self.commands.push(Command::Draw(call));
+    }
+}
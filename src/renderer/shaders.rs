@@ -0,0 +1,173 @@
+// Shader compilation, program linking, and the program cache.  Every program that uses
+// basic.frag used to compile its own copy; the cache compiles each source once per context
+// and links each vert/frag pair once.  `ProgramBuilder` is for one-off programs outside
+// the cache.
+
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use wasm_bindgen::JsValue;
+use web_sys::{WebGl2RenderingContext as GL, WebGlProgram, WebGlShader};
+
+use gloo_console::log;
+
+use super::errors::ShaderError;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct ShaderSource {
+    pub name: &'static str,
+    pub code: &'static str,
+}
+
+macro_rules! shader_source {
+    ($name:literal) => {
+        ShaderSource { name: $name, code: include_str!(concat!("../shaders/", $name)) }
+    };
+}
+
+pub const VEHICLE_100_VERT: ShaderSource = shader_source!("vehicle_100.vert");
+pub const TORPEDO_100_VERT: ShaderSource = shader_source!("torpedo_100.vert");
+pub const SPRITE_VERT: ShaderSource = shader_source!("sprite.vert");
+pub const SPRITE_FRAG: ShaderSource = shader_source!("sprite.frag");
+pub const EFFECTS_VERT: ShaderSource = shader_source!("effects.vert");
+pub const EFFECTS_FRAG: ShaderSource = shader_source!("effects.frag");
+pub const STARS_VERT: ShaderSource = shader_source!("stars.vert");
+pub const STARS_FRAG: ShaderSource = shader_source!("stars.frag");
+pub const NEBULA_VERT: ShaderSource = shader_source!("nebula.vert");
+pub const NEBULA_FRAG: ShaderSource = shader_source!("nebula.frag");
+pub const POST_VERT: ShaderSource = shader_source!("post.vert");
+pub const PHOSPHOR_FRAG: ShaderSource = shader_source!("phosphor.frag");
+pub const BLUR_FRAG: ShaderSource = shader_source!("blur.frag");
+pub const COMPOSITE_FRAG: ShaderSource = shader_source!("composite.frag");
+pub const BASIC_FRAG: ShaderSource = shader_source!("basic.frag");
+pub const PARTICLES_VERT: ShaderSource = shader_source!("particles.vert");
+pub const PARTICLES_FRAG: ShaderSource = shader_source!("particles.frag");
+pub const BARE_VERT: ShaderSource = shader_source!("bare.vert");
+pub const BARE_FRAG: ShaderSource = shader_source!("bare.frag");
+
+// Programs that declare `uniform Camera { mat4 u_projection; }` read the world-to-clip
+// projection from the buffer the renderer binds here.
+pub const CAMERA_BLOCK: &str = "Camera";
+pub const CAMERA_BINDING: u32 = 0;
+// and those that declare `uniform Gravity`, laid out in gravity.rs, read the attractors
+pub const GRAVITY_BLOCK: &str = "Gravity";
+pub const GRAVITY_BINDING: u32 = 1;
+
+const BLOCK_BINDINGS: [(&str, u32); 2] = [(CAMERA_BLOCK, CAMERA_BINDING), (GRAVITY_BLOCK, GRAVITY_BINDING)];
+
+pub struct Program {
+    pub program: WebGlProgram,
+}
+
+// Compiles both shaders and links them, checking status at every step.  Programs that
+// write transform feedback name their varyings before the link.
+pub struct ProgramBuilder<'a> {
+    gl: &'a GL,
+    vert: ShaderSource,
+    frag: ShaderSource,
+    varyings: &'a [&'a str],
+    buffer_mode: u32,
+}
+
+impl<'a> ProgramBuilder<'a> {
+    pub fn new(gl: &'a GL, vert: ShaderSource, frag: ShaderSource) -> Self {
+        Self { gl, vert, frag, varyings: &[], buffer_mode: GL::SEPARATE_ATTRIBS }
+    }
+
+    // `buffer_mode` is SEPARATE_ATTRIBS (one buffer per varying) or INTERLEAVED_ATTRIBS.
+    pub fn transform_feedback_varyings(mut self, varyings: &'a [&'a str], buffer_mode: u32) -> Self {
+        self.varyings = varyings;
+        self.buffer_mode = buffer_mode;
+        self
+    }
+
+    pub fn build(self) -> Result<WebGlProgram, ShaderError> {
+        let vert_shader = compile(self.gl, GL::VERTEX_SHADER, self.vert)?;
+        let frag_shader = compile(self.gl, GL::FRAGMENT_SHADER, self.frag)?;
+        link(self.gl, (self.vert, &vert_shader), (self.frag, &frag_shader), self.varyings, self.buffer_mode)
+    }
+}
+
+pub fn compile(gl: &GL, kind: u32, source: ShaderSource) -> Result<WebGlShader, ShaderError> {
+    let shader = gl.create_shader(kind).ok_or(ShaderError::Create("shader"))?;
+    gl.shader_source(&shader, source.code);
+    gl.compile_shader(&shader);
+
+    let shader_log = gl.get_shader_info_log(&shader).unwrap_or_default();
+    if !gl.get_shader_parameter(&shader, GL::COMPILE_STATUS).as_bool().unwrap_or(false) {
+        gl.delete_shader(Some(&shader));
+        return Err(ShaderError::compile(source.name, source.code, shader_log));
+    }
+    // warnings
+    if !shader_log.trim().is_empty() {
+        log!(format!("{} shader log: ", source.name), shader_log);
+    }
+    Ok(shader)
+}
+
+fn link(
+    gl: &GL,
+    (vert, vert_shader): (ShaderSource, &WebGlShader),
+    (frag, frag_shader): (ShaderSource, &WebGlShader),
+    varyings: &[&str],
+    buffer_mode: u32,
+) -> Result<WebGlProgram, ShaderError> {
+    let program = gl.create_program().ok_or(ShaderError::Create("program"))?;
+    gl.attach_shader(&program, vert_shader);
+    gl.attach_shader(&program, frag_shader);
+    if !varyings.is_empty() {
+        let names: js_sys::Array = varyings.iter().map(|v| JsValue::from_str(v)).collect();
+        gl.transform_feedback_varyings(&program, &names, buffer_mode);
+    }
+    gl.link_program(&program);
+
+    if !gl.get_program_parameter(&program, GL::LINK_STATUS).as_bool().unwrap_or(false) {
+        let log = gl.get_program_info_log(&program).unwrap_or_default();
+        gl.delete_program(Some(&program));
+        return Err(ShaderError::Link { vert: vert.name, frag: frag.name, log });
+    }
+
+    for (block, binding) in BLOCK_BINDINGS {
+        let index = gl.get_uniform_block_index(&program, block);
+        if index != GL::INVALID_INDEX {
+            gl.uniform_block_binding(&program, index, binding);
+        }
+    }
+    Ok(program)
+}
+
+pub struct ShaderCache {
+    gl: Rc<GL>,
+    shaders: HashMap<&'static str, WebGlShader>,
+    programs: HashMap<(&'static str, &'static str), Rc<Program>>,
+}
+
+impl ShaderCache {
+    pub fn new(gl: Rc<GL>) -> Self {
+        Self { gl, shaders: HashMap::new(), programs: HashMap::new() }
+    }
+
+    pub fn program(&mut self, vert: ShaderSource, frag: ShaderSource) -> Result<Rc<Program>, ShaderError> {
+        if let Some(program) = self.programs.get(&(vert.name, frag.name)) {
+            return Ok(program.clone());
+        }
+
+        let vert_shader = self.shader(GL::VERTEX_SHADER, vert)?;
+        let frag_shader = self.shader(GL::FRAGMENT_SHADER, frag)?;
+
+        let program = link(&self.gl, (vert, &vert_shader), (frag, &frag_shader), &[], GL::SEPARATE_ATTRIBS)?;
+        let program = Rc::new(Program { program });
+        self.programs.insert((vert.name, frag.name), program.clone());
+        Ok(program)
+    }
+
+    fn shader(&mut self, kind: u32, source: ShaderSource) -> Result<WebGlShader, ShaderError> {
+        if let Some(shader) = self.shaders.get(source.name) {
+            return Ok(shader.clone());
+        }
+
+        let shader = compile(&self.gl, kind, source)?;
+        self.shaders.insert(source.name, shader.clone());
+        Ok(shader)
+    }
+}
@@ -0,0 +1,304 @@
+// The backdrop: a faint nebula and a field of stars, drawn before anything that moves.
+//
+// Everything is fixed in the arena, with no parallax, so the sky wraps with the ships and
+// nothing gives away where the torus is "cut".  Both the stars and the clouds come from
+// the match seed, so a replay shows the same sky as the match it recorded.  In place of
+// the random stars you can have the real sky, after Spacewar!'s "Expensive Planetarium".
+
+use std::cell::Cell;
+
+use serde::{Deserialize, Serialize};
+use web_sys::{
+    WebGl2RenderingContext as GL, WebGlBuffer, WebGlProgram, WebGlUniformLocation, WebGlVertexArrayObject,
+};
+
+use super::errors::RendererError;
+use super::shaders::{ProgramBuilder, NEBULA_FRAG, NEBULA_VERT, STARS_FRAG, STARS_VERT};
+use crate::simulation::rng::Rng;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum StarChart {
+    // random stars from the seed
+    #[default]
+    Procedural,
+    // the bright stars of the real sky
+    Planetarium,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Star {
+    pub position: [f32; 2],
+    // 0..1
+    pub brightness: f32,
+    // diameter, as a fraction of the arena's side
+    pub size: f32,
+    // 0..1, sets the star's twinkle rate and phase
+    pub twinkle: f32,
+}
+
+// laid out as stars.vert reads them
+const STAR_FLOATS: usize = 5;
+
+// keeps the sky from echoing anything else drawn from the same seed
+const SKY_STREAM: u64 = 0x5354_4152_4649_454c;
+
+// Far more faint stars than bright ones, a layer each.
+struct Layer {
+    count: usize,
+    brightness: (f32, f32),
+    size: f32,
+}
+
+const LAYERS: [Layer; 3] = [
+    Layer { count: 500, brightness: (0.15, 0.35), size: 0.0012 },
+    Layer { count: 150, brightness: (0.35, 0.65), size: 0.002 },
+    Layer { count: 30, brightness: (0.7, 1.0), size: 0.003 },
+];
+
+// (right ascension in hours, declination in degrees, visual magnitude), J2000, for the
+// stars down to about magnitude 2.9 that fall within CHART_DECLINATION
+#[rustfmt::skip]
+const BRIGHT_STARS: [(f32, f32, f32); 57] = [
+    (6.752, -16.72, -1.46), // Sirius
+    (6.399, -52.70, -0.74), // Canopus
+    (14.261, 19.18, -0.05), // Arcturus
+    (18.616, 38.78, 0.03),  // Vega
+    (5.278, 46.00, 0.08),   // Capella
+    (5.242, -8.20, 0.13),   // Rigel
+    (7.655, 5.22, 0.34),    // Procyon
+    (1.629, -57.24, 0.46),  // Achernar
+    (5.919, 7.41, 0.50),    // Betelgeuse
+    (19.846, 8.87, 0.77),   // Altair
+    (4.599, 16.51, 0.85),   // Aldebaran
+    (16.490, -26.43, 0.96), // Antares
+    (13.420, -11.16, 0.97), // Spica
+    (7.755, 28.03, 1.14),   // Pollux
+    (22.961, -29.62, 1.16), // Fomalhaut
+    (20.690, 45.28, 1.25),  // Deneb
+    (10.140, 11.97, 1.35),  // Regulus
+    (6.977, -28.97, 1.50),  // Adhara
+    (7.577, 31.89, 1.58),   // Castor
+    (17.560, -37.10, 1.62), // Shaula
+    (5.419, 6.35, 1.64),    // Bellatrix
+    (5.438, 28.61, 1.65),   // Elnath
+    (5.604, -1.20, 1.69),   // Alnilam
+    (22.137, -46.96, 1.74), // Alnair
+    (5.679, -1.94, 1.77),   // Alnitak
+    (12.900, 55.96, 1.77),  // Alioth
+    (11.062, 61.75, 1.79),  // Dubhe
+    (3.405, 49.86, 1.79),   // Mirfak
+    (7.140, -26.39, 1.83),  // Wezen
+    (18.403, -34.38, 1.85), // Kaus Australis
+    (13.792, 49.31, 1.86),  // Alkaid
+    (5.992, 44.95, 1.90),   // Menkalinan
+    (6.629, 16.40, 1.93),   // Alhena
+    (20.427, -56.74, 1.94), // Peacock
+    (6.378, -17.96, 1.98),  // Mirzam
+    (9.460, -8.66, 1.98),   // Alphard
+    (2.120, 23.46, 2.00),   // Hamal
+    (0.727, -17.99, 2.04),  // Diphda
+    (18.921, -26.30, 2.05), // Nunki
+    (1.162, 35.62, 2.05),   // Mirach
+    (0.140, 29.09, 2.06),   // Alpheratz
+    (17.582, 12.56, 2.08),  // Rasalhague
+    (5.796, -9.67, 2.09),   // Saiph
+    (2.065, 42.33, 2.10),   // Almach
+    (11.818, 14.57, 2.11),  // Denebola
+    (3.136, 40.96, 2.12),   // Algol
+    (20.370, 40.26, 2.23),  // Sadr
+    (5.533, -0.30, 2.23),   // Mintaka
+    (21.736, 9.88, 2.38),   // Enif
+    (23.063, 28.08, 2.42),  // Scheat
+    (21.310, 62.59, 2.45),  // Alderamin
+    (23.080, 15.21, 2.48),  // Markab
+    (3.038, 4.09, 2.54),    // Menkar
+    (15.738, 6.43, 2.63),   // Unukalhai
+    (14.848, -16.04, 2.75), // Zubenelgenubi
+    (0.221, 15.18, 2.83),   // Algenib
+    (3.791, 24.11, 2.87),   // Alcyone
+];
+
+// The chart spans the whole of right ascension across the arena, so it wraps just as
+// the sky does, and this much declination either side of the equator up and down.
+const CHART_DECLINATION: f32 = 65.0;
+
+// A seed's sky: where the stars are, and which part of the noise the clouds come from.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Sky {
+    pub stars: Vec<Star>,
+    pub nebula_offset: [f32; 2],
+}
+
+impl Sky {
+    pub fn generate(seed: u64, chart: StarChart) -> Self {
+        let mut rng = Rng::new(seed ^ SKY_STREAM);
+        let nebula_offset = [rng.range(0.0, 64.0), rng.range(0.0, 64.0)];
+
+        let stars = match chart {
+            StarChart::Procedural => LAYERS
+                .iter()
+                .flat_map(|layer| std::iter::repeat_n(layer, layer.count))
+                .map(|layer| Star {
+                    position: [rng.range(-1.0, 1.0), rng.range(-1.0, 1.0)],
+                    brightness: rng.range(layer.brightness.0, layer.brightness.1),
+                    size: layer.size,
+                    twinkle: rng.next_f32(),
+                })
+                .collect(),
+            StarChart::Planetarium => BRIGHT_STARS
+                .iter()
+                .map(|&(ascension, declination, magnitude)| {
+                    // Sirius at full brightness, the faintest listed at about a third
+                    let brightness = (1.0 - (magnitude + 1.5) * 0.15).clamp(0.3, 1.0);
+                    Star {
+                        // east is to the left, looking up
+                        position: [1.0 - ascension / 12.0, declination / CHART_DECLINATION],
+                        brightness,
+                        size: 0.0015 + 0.003 * brightness,
+                        twinkle: rng.next_f32(),
+                    }
+                })
+                .collect(),
+        };
+        Self { stars, nebula_offset }
+    }
+}
+
+pub struct Starfield {
+    stars_program: WebGlProgram,
+    stars_vao: WebGlVertexArrayObject,
+    stars_buffer: WebGlBuffer,
+    star_count: Cell<i32>,
+    time_loc: Option<WebGlUniformLocation>,
+    point_scale_loc: Option<WebGlUniformLocation>,
+    nebula_program: WebGlProgram,
+    nebula_vao: WebGlVertexArrayObject,
+    offset_loc: Option<WebGlUniformLocation>,
+    nebula_offset: Cell<[f32; 2]>,
+    // the sky the buffers hold
+    showing: Cell<Option<(u64, StarChart)>>,
+}
+
+impl Starfield {
+    pub fn new(gl: &GL) -> Result<Self, RendererError> {
+        let stars_program = ProgramBuilder::new(gl, STARS_VERT, STARS_FRAG).build()?;
+        let stars_vao = gl.create_vertex_array().ok_or("could not create vertex array")?;
+        let stars_buffer = gl.create_buffer().ok_or("could not create buffer")?;
+        gl.bind_vertex_array(Some(&stars_vao));
+        gl.bind_buffer(GL::ARRAY_BUFFER, Some(&stars_buffer));
+        let stride = (STAR_FLOATS * 4) as i32;
+        // position, brightness, size, twinkle
+        for (location, (size, offset)) in [(2, 0), (1, 2), (1, 3), (1, 4)].iter().enumerate() {
+            gl.vertex_attrib_pointer_with_i32(location as u32, *size, GL::FLOAT, false, stride, offset * 4);
+            gl.enable_vertex_attrib_array(location as u32);
+        }
+
+        let nebula_program = ProgramBuilder::new(gl, NEBULA_VERT, NEBULA_FRAG).build()?;
+        let nebula_vao = gl.create_vertex_array().ok_or("could not create vertex array")?;
+        let quad = gl.create_buffer().ok_or("could not create buffer")?;
+        gl.bind_vertex_array(Some(&nebula_vao));
+        gl.bind_buffer(GL::ARRAY_BUFFER, Some(&quad));
+        let js_quad = js_sys::Float32Array::from(&[-1.0, -1.0, 1.0, -1.0, -1.0, 1.0, 1.0, 1.0][..]);
+        gl.buffer_data_with_array_buffer_view(GL::ARRAY_BUFFER, &js_quad, GL::STATIC_DRAW);
+        gl.vertex_attrib_pointer_with_i32(0, 2, GL::FLOAT, false, 0, 0);
+        gl.enable_vertex_attrib_array(0);
+
+        gl.bind_vertex_array(None);
+        gl.bind_buffer(GL::ARRAY_BUFFER, None);
+
+        Ok(Self {
+            time_loc: gl.get_uniform_location(&stars_program, "u_time"),
+            point_scale_loc: gl.get_uniform_location(&stars_program, "u_point_scale"),
+            offset_loc: gl.get_uniform_location(&nebula_program, "u_offset"),
+            stars_program,
+            stars_vao,
+            stars_buffer,
+            star_count: Cell::new(0),
+            nebula_program,
+            nebula_vao,
+            nebula_offset: Cell::new([0.0, 0.0]),
+            showing: Cell::new(None),
+        })
+    }
+
+    // Regenerates the sky only when the seed or chart changes.
+    fn show(&self, gl: &GL, seed: u64, chart: StarChart) {
+        if self.showing.get() == Some((seed, chart)) {
+            return;
+        }
+        let sky = Sky::generate(seed, chart);
+        let mut data: Vec<f32> = Vec::with_capacity(sky.stars.len() * STAR_FLOATS);
+        for star in sky.stars.iter() {
+            data.extend_from_slice(&star.position);
+            data.extend_from_slice(&[star.brightness, star.size, star.twinkle]);
+        }
+        gl.bind_buffer(GL::ARRAY_BUFFER, Some(&self.stars_buffer));
+        let js_data = js_sys::Float32Array::from(data.as_slice());
+        gl.buffer_data_with_array_buffer_view(GL::ARRAY_BUFFER, &js_data, GL::STATIC_DRAW);
+        gl.bind_buffer(GL::ARRAY_BUFFER, None);
+
+        self.star_count.set(sky.stars.len() as i32);
+        self.nebula_offset.set(sky.nebula_offset);
+        self.showing.set(Some((seed, chart)));
+    }
+
+    // `time` in seconds drives the twinkle; `point_scale` is the arena's side in pixels.
+    pub fn draw(&self, gl: &GL, seed: u64, chart: StarChart, time: f32, point_scale: f32) {
+        self.show(gl, seed, chart);
+
+        gl.use_program(Some(&self.nebula_program));
+        let [x, y] = self.nebula_offset.get();
+        gl.uniform2f(self.offset_loc.as_ref(), x, y);
+        gl.bind_vertex_array(Some(&self.nebula_vao));
+        gl.draw_arrays(GL::TRIANGLE_STRIP, 0, 4);
+
+        gl.use_program(Some(&self.stars_program));
+        gl.uniform1f(self.time_loc.as_ref(), time);
+        gl.uniform1f(self.point_scale_loc.as_ref(), point_scale);
+        gl.bind_vertex_array(Some(&self.stars_vao));
+        gl.draw_arrays(GL::POINTS, 0, self.star_count.get());
+        gl.bind_vertex_array(None);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn the_seed_decides_the_sky() {
+        assert_eq!(Sky::generate(9, StarChart::Procedural), Sky::generate(9, StarChart::Procedural));
+        assert_ne!(Sky::generate(9, StarChart::Procedural), Sky::generate(10, StarChart::Procedural));
+        // the real sky only twinkles differently
+        let (one, other) = (Sky::generate(9, StarChart::Planetarium), Sky::generate(10, StarChart::Planetarium));
+        let positions = |sky: &Sky| sky.stars.iter().map(|s| s.position).collect::<Vec<_>>();
+        assert_eq!(positions(&one), positions(&other));
+        assert_ne!(one, other);
+    }
+
+    #[test]
+    fn every_layer_is_filled_faintest_first() {
+        let sky = Sky::generate(3, StarChart::Procedural);
+        assert_eq!(LAYERS.iter().map(|layer| layer.count).sum::<usize>(), 680);
+        assert_eq!(sky.stars.len(), 680);
+
+        let mut stars = sky.stars.iter();
+        for layer in LAYERS.iter() {
+            for star in stars.by_ref().take(layer.count) {
+                assert_eq!(star.size, layer.size);
+                assert!((layer.brightness.0..=layer.brightness.1).contains(&star.brightness));
+                assert!(star.position.iter().all(|c| (-1.0..=1.0).contains(c)));
+            }
+        }
+    }
+
+    #[test]
+    fn the_planetarium_fits_the_arena() {
+        let sky = Sky::generate(3, StarChart::Planetarium);
+        assert_eq!(sky.stars.len(), BRIGHT_STARS.len());
+        for star in sky.stars.iter() {
+            assert!(star.position.iter().all(|c| (-1.0..=1.0).contains(c)), "{:?}", star.position);
+            assert!((0.3..=1.0).contains(&star.brightness));
+        }
+    }
+}
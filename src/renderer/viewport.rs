@@ -0,0 +1,128 @@
+// Canvas sizing and the world-to-clip projection.
+//
+// The canvas follows its CSS size times devicePixelRatio, so it is sharp on HiDPI screens
+// and never stretched.  The arena is the square [-1, 1] x [-1, 1] in world space (see
+// `Vehicle_100::wrap`); it is fitted to the largest centred square in the canvas and the
+// rest is letterboxed.
+
+use std::cell::Cell;
+use std::rc::Rc;
+
+use cgmath::Matrix4;
+use wasm_bindgen::prelude::*;
+use web_sys::{window, HtmlCanvasElement};
+
+// Components put this on their canvas instead of fixed width/height attributes; the
+// backing store is sized from it.
+pub const CANVAS_STYLE: &str = "display: block; width: 100%; height: 90vh;";
+
+// HTML laid over the arena: put the canvas in a box with CANVAS_BOX_STYLE and this on a
+// sibling.  It covers the same centred square the letterboxing leaves (the smaller of
+// the canvas's two sides) and sizes text with `cqw`, so it scales with the arena.
+pub const CANVAS_BOX_STYLE: &str = "position: relative;";
+pub const ARENA_OVERLAY_STYLE: &str = "position: absolute; top: 50%; left: 50%; \
+    transform: translate(-50%, -50%); width: min(100%, 90vh); aspect-ratio: 1; \
+    container-type: size; pointer-events: none;";
+
+// ResizeObserver is still behind web_sys_unstable_apis, so bind it directly.
+#[wasm_bindgen(inline_js = r#"
+export function observe_resize(element, callback) {
+  const observer = new ResizeObserver(() => callback());
+  observer.observe(element);
+  return observer;
+}
+
+export function disconnect_observer(observer) {
+  observer.disconnect();
+}"#)]
+extern "C" {
+    fn observe_resize(element: &HtmlCanvasElement, callback: &Closure<dyn FnMut()>) -> JsValue;
+    fn disconnect_observer(observer: &JsValue);
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Viewport {
+    // backing store size, in device pixels
+    pub width: u32,
+    pub height: u32,
+    // x, y, side of the arena square, in device pixels from the bottom left
+    pub arena: [i32; 3],
+}
+
+impl Viewport {
+    pub fn fit(css_width: f64, css_height: f64, pixel_ratio: f64) -> Self {
+        let width = (css_width * pixel_ratio).round().max(1.0) as u32;
+        let height = (css_height * pixel_ratio).round().max(1.0) as u32;
+        let side = width.min(height);
+        Self {
+            width,
+            height,
+            arena: [((width - side) / 2) as i32, ((height - side) / 2) as i32, side as i32],
+        }
+    }
+
+    // Squeezes the world square along the longer axis so it lands on the arena.
+    pub fn projection(&self) -> Matrix4<f32> {
+        let side = self.arena[2] as f32;
+        Matrix4::from_nonuniform_scale(side / self.width as f32, side / self.height as f32, 1.0)
+    }
+}
+
+// Flags the canvas for a resize when its CSS box or the device pixel ratio changes.
+// Disconnects the observer when dropped.
+pub struct ResizeWatcher {
+    canvas: HtmlCanvasElement,
+    observer: JsValue,
+    _callback: Closure<dyn FnMut()>,
+    dirty: Rc<Cell<bool>>,
+    pixel_ratio: Cell<f64>,
+}
+
+impl ResizeWatcher {
+    pub fn new(canvas: &HtmlCanvasElement) -> Self {
+        let dirty = Rc::new(Cell::new(true));
+        let flag = dirty.clone();
+        let callback = Closure::wrap(Box::new(move || flag.set(true)) as Box<dyn FnMut()>);
+        let observer = observe_resize(canvas, &callback);
+        Self {
+            canvas: canvas.clone(),
+            observer,
+            _callback: callback,
+            dirty,
+            pixel_ratio: Cell::new(device_pixel_ratio()),
+        }
+    }
+
+    // The new viewport if anything changed since the last call.  Also resizes the
+    // canvas backing store to match.
+    pub fn take_resize(&self) -> Option<Viewport> {
+        let pixel_ratio = device_pixel_ratio();
+        if !self.dirty.replace(false) && pixel_ratio == self.pixel_ratio.get() {
+            return None;
+        }
+        self.pixel_ratio.set(pixel_ratio);
+
+        let viewport = Viewport::fit(
+            self.canvas.client_width() as f64,
+            self.canvas.client_height() as f64,
+            pixel_ratio,
+        );
+        if self.canvas.width() != viewport.width {
+            self.canvas.set_width(viewport.width);
+        }
+        if self.canvas.height() != viewport.height {
+            self.canvas.set_height(viewport.height);
+        }
+        Some(viewport)
+    }
+}
+
+impl Drop for ResizeWatcher {
+    fn drop(&mut self) {
+        disconnect_observer(&self.observer);
+    }
+}
+
+fn device_pixel_ratio() -> f64 {
+    window().map(|w| w.device_pixel_ratio()).unwrap_or(1.0)
+}
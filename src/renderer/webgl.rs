@@ -0,0 +1,197 @@
+// The WebGL2 backend.  It owns the context, compiles and caches the shader programs,
+// uploads each mesh once into a VAO, keeps the canvas sized to the page, and holds the
+// sky, the particle effects and the post effects with their GL objects.
+//
+// Every GL object lives in `GlObjects`, built only from constants (shader sources, ship
+// designs, buffer sizes), so when a lost context comes back the whole lot is simply
+// built again.
+
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use cgmath::Matrix4;
+use wasm_bindgen::JsCast;
+use web_sys::{HtmlCanvasElement, WebGl2RenderingContext as GL, WebGlBuffer};
+
+use super::backend::{ContextStatus, DrawCall, RenderBackend};
+use super::context::ContextWatcher;
+use super::effects::ParticleEffects;
+use super::errors::{report, RendererError};
+use super::frame::MeshId;
+use super::gravity::GravityBuffer;
+use super::mesh::{Mesh, INSTANCE_FLOATS, TORPEDO_100_VERTICES};
+use super::post::{PostProcess, PostQuality};
+use super::shaders::{ShaderCache, CAMERA_BINDING, SPRITE_FRAG, SPRITE_VERT};
+use super::starfield::Starfield;
+use super::viewport::{ResizeWatcher, Viewport};
+use crate::simulation::ShipDesign;
+
+pub struct WebGlBackend {
+    gl: Rc<GL>,
+    resize: ResizeWatcher,
+    context: ContextWatcher,
+    objects: GlObjects,
+    // set when a restored context couldn't be rebuilt; it stays lost from then on
+    broken: bool,
+}
+
+impl WebGlBackend {
+    pub fn from_canvas(canvas: &HtmlCanvasElement) -> Result<Self, RendererError> {
+        let gl: GL = canvas
+            .get_context("webgl2")
+            .ok()
+            .flatten()
+            .ok_or(RendererError::Unavailable)?
+            .dyn_into::<GL>()
+            .map_err(|_| "webgl2 context is not a WebGl2RenderingContext")?;
+
+        let gl = Rc::new(gl);
+        Ok(Self {
+            resize: ResizeWatcher::new(canvas),
+            context: ContextWatcher::new(canvas),
+            objects: GlObjects::new(&gl)?,
+            broken: false,
+            gl,
+        })
+    }
+}
+
+struct GlObjects {
+    shaders: ShaderCache,
+    meshes: HashMap<MeshId, Mesh>,
+    camera: WebGlBuffer,
+    gravity: GravityBuffer,
+    starfield: Starfield,
+    effects: ParticleEffects,
+    post: PostProcess,
+}
+
+impl GlObjects {
+    fn new(gl: &Rc<GL>) -> Result<Self, RendererError> {
+        let camera = gl.create_buffer().ok_or("could not create buffer")?;
+        gl.bind_buffer(GL::UNIFORM_BUFFER, Some(&camera));
+        gl.buffer_data_with_i32(GL::UNIFORM_BUFFER, 16 * 4, GL::DYNAMIC_DRAW);
+        gl.bind_buffer(GL::UNIFORM_BUFFER, None);
+        gl.bind_buffer_base(GL::UNIFORM_BUFFER, CAMERA_BINDING, Some(&camera));
+
+        let mut shaders = ShaderCache::new(gl.clone());
+        // compiled now so a broken sprite shader fails here, not on the first frame
+        shaders.program(SPRITE_VERT, SPRITE_FRAG)?;
+        let mut meshes = HashMap::new();
+        for design in ShipDesign::ALL {
+            meshes.insert(MeshId::Ship(design), Mesh::new(gl, &design.mesh().triangle_list())?);
+        }
+        meshes.insert(MeshId::Torpedo, Mesh::new(gl, &TORPEDO_100_VERTICES)?);
+
+        gl.enable(GL::BLEND);
+        gl.blend_func(GL::ONE, GL::ONE_MINUS_SRC_ALPHA);
+
+        Ok(Self {
+            shaders,
+            meshes,
+            camera,
+            gravity: GravityBuffer::new(gl)?,
+            starfield: Starfield::new(gl)?,
+            effects: ParticleEffects::new(gl)?,
+            post: PostProcess::new(gl)?,
+        })
+    }
+}
+
+impl RenderBackend for WebGlBackend {
+    fn take_resize(&mut self) -> Option<Viewport> {
+        self.resize.take_resize()
+    }
+
+    fn context_status(&mut self) -> ContextStatus {
+        if self.broken || self.gl.is_context_lost() {
+            return ContextStatus::Lost;
+        }
+        if !self.context.take_restored() {
+            return ContextStatus::Ready;
+        }
+        match GlObjects::new(&self.gl) {
+            Ok(objects) => {
+                self.objects = objects;
+                ContextStatus::Restored
+            }
+            Err(e) => {
+                report(&e);
+                self.broken = true;
+                ContextStatus::Lost
+            }
+        }
+    }
+
+    fn bind_canvas(&mut self) {
+        self.gl.bind_framebuffer(GL::FRAMEBUFFER, None);
+    }
+
+    fn bind_scene(&mut self, arena_side: i32, quality: PostQuality) -> Result<(), RendererError> {
+        self.objects.post.begin(&self.gl, arena_side, quality)
+    }
+
+    fn set_viewport(&mut self, [x, y, width, height]: [i32; 4]) {
+        self.gl.viewport(x, y, width, height);
+    }
+
+    fn set_scissor(&mut self, rect: Option<[i32; 4]>) {
+        match rect {
+            Some([x, y, width, height]) => {
+                self.gl.enable(GL::SCISSOR_TEST);
+                self.gl.scissor(x, y, width, height);
+            }
+            None => self.gl.disable(GL::SCISSOR_TEST),
+        }
+    }
+
+    fn clear(&mut self, [r, g, b, a]: [f32; 4]) {
+        self.gl.clear_color(r, g, b, a);
+        self.gl.clear(GL::COLOR_BUFFER_BIT);
+    }
+
+    fn set_projection(&mut self, projection: Matrix4<f32>) {
+        let gl = &self.gl;
+        let matrix: &[f32; 16] = projection.as_ref();
+        let js_matrix = js_sys::Float32Array::from(&matrix[..]);
+        gl.bind_buffer(GL::UNIFORM_BUFFER, Some(&self.objects.camera));
+        gl.buffer_sub_data_with_i32_and_array_buffer_view(GL::UNIFORM_BUFFER, 0, &js_matrix);
+        gl.bind_buffer(GL::UNIFORM_BUFFER, None);
+    }
+
+    fn draw(&mut self, call: DrawCall) {
+        let gl = &self.gl;
+        let objects = &mut self.objects;
+        match call {
+            DrawCall::Sky { seed, chart, time, point_scale } => {
+                objects.starfield.draw(gl, seed, chart, time, point_scale);
+            }
+            DrawCall::Particles { bursts, gravity, delta, point_size } => {
+                objects.gravity.upload(gl, &gravity);
+                objects.effects.spawn(gl, &bursts);
+                objects.effects.step(gl, delta, point_size);
+            }
+            DrawCall::Sprites { vert, frag, mesh, instances } => {
+                let program = match objects.shaders.program(vert, frag) {
+                    Ok(program) => program,
+                    Err(e) => return report(&e),
+                };
+                let mesh = match objects.meshes.get(&mesh) {
+                    Some(mesh) => mesh,
+                    None => return,
+                };
+                gl.use_program(Some(&program.program));
+                gl.bind_vertex_array(Some(&mesh.vao));
+                gl.bind_buffer(GL::ARRAY_BUFFER, Some(&mesh.instance_buffer));
+                let js_instances = js_sys::Float32Array::from(instances.as_slice());
+                gl.buffer_data_with_array_buffer_view(GL::ARRAY_BUFFER, &js_instances, GL::STREAM_DRAW);
+                let instance_count = (instances.len() / INSTANCE_FLOATS) as i32;
+                gl.draw_arrays_instanced(GL::TRIANGLES, 0, mesh.vertex_count, instance_count);
+                gl.bind_vertex_array(None);
+                gl.bind_buffer(GL::ARRAY_BUFFER, None);
+            }
+            DrawCall::PostProcess { settings, elapsed } => objects.post.process(gl, &settings, elapsed),
+            DrawCall::Composite { settings, arena_side } => objects.post.composite(gl, &settings, arena_side),
+        }
+    }
+}
@@ -0,0 +1,33 @@
+// Getting replay files in and out of the browser.
+
+use wasm_bindgen::JsCast;
+use wasm_bindgen::JsValue;
+use wasm_bindgen_futures::JsFuture;
+use web_sys::{Blob, File, HtmlAnchorElement, Url};
+
+use super::{Replay, ReplayError, FILE_EXTENSION};
+
+// Hand the replay to the browser as a file download.
+pub fn download_replay(replay: &Replay, file_stem: &str) -> Result<(), JsValue> {
+    let bytes = js_sys::Uint8Array::from(replay.to_bytes().as_slice());
+    let parts = js_sys::Array::of1(&bytes);
+    let blob = Blob::new_with_u8_array_sequence(&parts)?;
+    let url = Url::create_object_url_with_blob(&blob)?;
+
+    let document = web_sys::window().unwrap().document().unwrap();
+    let anchor: HtmlAnchorElement = document.create_element("a")?.dyn_into()?;
+    anchor.set_href(&url);
+    anchor.set_download(&format!("{}.{}", file_stem, FILE_EXTENSION));
+    anchor.click();
+
+    Url::revoke_object_url(&url)
+}
+
+// Read a file picked from an <input type="file"> or dropped onto the page.
+pub async fn read_replay(file: File) -> Result<Replay, String> {
+    let buffer = JsFuture::from(file.array_buffer())
+        .await
+        .map_err(|e| format!("could not read {}: {:?}", file.name(), e))?;
+    let bytes = js_sys::Uint8Array::new(&buffer).to_vec();
+    Replay::from_bytes(&bytes).map_err(|e: ReplayError| format!("{}: {}", file.name(), e))
+}
@@ -0,0 +1,393 @@
+// Match recordings.  A replay is the seed, the match config (rules and all) and the
+// controls of both players for every tick; re-running those through
+// `simulation::update_game_state` reproduces the match exactly, so nothing about the world
+// itself is stored.
+//
+// File layout, all integers little-endian:
+//
+//   "SWRP"  version:u8  seed:u64  config_len:u32  config (JSON)
+//   final_tick:u32  final_hash:u64  run_count:u32  runs...
+//
+// Inputs are run-length encoded as (length varint, player one bits, player two bits),
+// which keeps a few minutes of play down to a few kilobytes since most ticks repeat
+// the previous one (usually nothing pressed).
+//
+// Version 1 kept the round count and length in the config itself, from before there were
+// rules to put them in; those files still load, under the classic rules.
+
+pub mod browser;
+#[cfg(test)]
+mod regression;
+
+use std::fmt;
+
+use serde::Deserialize;
+
+use crate::simulation::{
+    create_game_state, state_hash, update_game_state, Controls, GameMode, GameState, MatchConfig,
+    ShipDesign, TickInput,
+};
+
+const MAGIC: &[u8; 4] = b"SWRP";
+const VERSION: u8 = 2;
+
+pub const FILE_EXTENSION: &str = "swrp";
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Replay {
+    pub seed: u64,
+    pub config: MatchConfig,
+    pub inputs: Vec<TickInput>,
+    // State hash after the last recorded tick, as seen by whoever recorded it.
+    pub final_hash: u64,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum ReplayError {
+    BadMagic,
+    UnsupportedVersion(u8),
+    Truncated,
+    BadConfig(String),
+    LengthMismatch { expected: u32, found: u32 },
+}
+
+impl fmt::Display for ReplayError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReplayError::BadMagic => write!(f, "not a replay file"),
+            ReplayError::UnsupportedVersion(v) => write!(f, "unsupported replay version {}", v),
+            ReplayError::Truncated => write!(f, "replay file is truncated"),
+            ReplayError::BadConfig(e) => write!(f, "replay match config is invalid: {}", e),
+            ReplayError::LengthMismatch { expected, found } => write!(
+                f,
+                "replay header says {} ticks but the input stream holds {}",
+                expected, found
+            ),
+        }
+    }
+}
+
+impl Replay {
+    pub fn new(seed: u64, config: MatchConfig) -> Self {
+        let final_hash = state_hash(&create_game_state(seed, config.clone()));
+        Self { seed, config, inputs: vec![], final_hash }
+    }
+
+    pub fn tick_count(&self) -> u32 {
+        self.inputs.len() as u32
+    }
+
+    pub fn initial_state(&self) -> GameState {
+        create_game_state(self.seed, self.config.clone())
+    }
+
+    // Step `game_state` forward from wherever it is up to `tick`, bounded by the recording.
+    pub fn advance(&self, game_state: &mut GameState, tick: u32) {
+        let end = tick.min(self.tick_count());
+        while game_state.tick < end {
+            update_game_state(game_state, &self.inputs[game_state.tick as usize]);
+        }
+    }
+
+    // Headless run of the whole recording.
+    #[cfg(test)]
+    pub fn simulate(&self) -> GameState {
+        let mut game_state = self.initial_state();
+        self.advance(&mut game_state, self.tick_count());
+        game_state
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let config = serde_json::to_vec(&self.config).expect("match config serializes");
+
+        let mut out = Vec::with_capacity(64 + config.len() + self.inputs.len() / 8);
+        out.extend_from_slice(MAGIC);
+        out.push(VERSION);
+        out.extend_from_slice(&self.seed.to_le_bytes());
+        out.extend_from_slice(&(config.len() as u32).to_le_bytes());
+        out.extend_from_slice(&config);
+        out.extend_from_slice(&self.tick_count().to_le_bytes());
+        out.extend_from_slice(&self.final_hash.to_le_bytes());
+
+        let runs = encode_runs(&self.inputs);
+        out.extend_from_slice(&(runs.len() as u32).to_le_bytes());
+        for (length, input) in runs {
+            write_varint(&mut out, length);
+            out.push(input.players[0].0);
+            out.push(input.players[1].0);
+        }
+        out
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Replay, ReplayError> {
+        let mut reader = Reader { bytes, cursor: 0 };
+
+        if reader.take(4)? != MAGIC {
+            return Err(ReplayError::BadMagic);
+        }
+        let version = reader.u8()?;
+        if version != 1 && version != VERSION {
+            return Err(ReplayError::UnsupportedVersion(version));
+        }
+        let seed = reader.u64()?;
+        let config_len = reader.u32()? as usize;
+        let config = read_config(version, reader.take(config_len)?)?;
+        let tick_count = reader.u32()?;
+        let final_hash = reader.u64()?;
+
+        let run_count = reader.u32()?;
+        let mut inputs = Vec::with_capacity(tick_count as usize);
+        for _ in 0..run_count {
+            let length = reader.varint()?;
+            let input = TickInput {
+                players: [Controls(reader.u8()?), Controls(reader.u8()?)],
+            };
+            if inputs.len() as u64 + length as u64 > tick_count as u64 {
+                return Err(ReplayError::LengthMismatch {
+                    expected: tick_count,
+                    found: (inputs.len() as u32).saturating_add(length),
+                });
+            }
+            inputs.extend(std::iter::repeat_n(input, length as usize));
+        }
+        if inputs.len() as u32 != tick_count {
+            return Err(ReplayError::LengthMismatch { expected: tick_count, found: inputs.len() as u32 });
+        }
+
+        Ok(Replay { seed, config, inputs, final_hash })
+    }
+}
+
+// The match config as version 1 wrote it.
+#[derive(Deserialize)]
+#[serde(default)]
+struct ConfigV1 {
+    mode: GameMode,
+    best_of: u32,
+    round_ticks: u32,
+    ships: [ShipDesign; 2],
+}
+
+impl Default for ConfigV1 {
+    fn default() -> Self {
+        let config = MatchConfig::default();
+        Self {
+            mode: config.mode,
+            best_of: config.rules.scoring.best_of,
+            round_ticks: config.rules.round.round_ticks,
+            ships: config.ships,
+        }
+    }
+}
+
+fn read_config(version: u8, json: &[u8]) -> Result<MatchConfig, ReplayError> {
+    let bad_config = |e: String| ReplayError::BadConfig(e);
+    let config = match version {
+        1 => {
+            let old: ConfigV1 = serde_json::from_slice(json).map_err(|e| bad_config(e.to_string()))?;
+            let mut config = MatchConfig { mode: old.mode, ships: old.ships, ..MatchConfig::default() };
+            config.rules.scoring.best_of = old.best_of;
+            config.rules.round.round_ticks = old.round_ticks;
+            config
+        }
+        _ => serde_json::from_slice::<MatchConfig>(json).map_err(|e| bad_config(e.to_string()))?,
+    };
+    config.rules.validate().map_err(|e| bad_config(e.to_string()))?;
+    Ok(config)
+}
+
+// Appends ticks as the match is played.  Owned by the game loop; the download button
+// takes a copy of whatever has been recorded so far.
+pub struct ReplayRecorder {
+    replay: Replay,
+}
+
+impl ReplayRecorder {
+    pub fn new(seed: u64, config: MatchConfig) -> Self {
+        Self { replay: Replay::new(seed, config) }
+    }
+
+    pub fn record(&mut self, input: TickInput, game_state_after: &GameState) {
+        self.replay.inputs.push(input);
+        self.replay.final_hash = state_hash(game_state_after);
+    }
+
+    pub fn replay(&self) -> &Replay {
+        &self.replay
+    }
+}
+
+fn encode_runs(inputs: &[TickInput]) -> Vec<(u32, TickInput)> {
+    let mut runs: Vec<(u32, TickInput)> = vec![];
+    for input in inputs {
+        match runs.last_mut() {
+            Some((length, last)) if last == input => *length += 1,
+            _ => runs.push((1, *input)),
+        }
+    }
+    runs
+}
+
+fn write_varint(out: &mut Vec<u8>, mut value: u32) {
+    while value >= 0x80 {
+        out.push((value as u8 & 0x7f) | 0x80);
+        value >>= 7;
+    }
+    out.push(value as u8);
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    cursor: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, n: usize) -> Result<&'a [u8], ReplayError> {
+        let end = self.cursor.checked_add(n).ok_or(ReplayError::Truncated)?;
+        let slice = self.bytes.get(self.cursor..end).ok_or(ReplayError::Truncated)?;
+        self.cursor = end;
+        Ok(slice)
+    }
+
+    fn u8(&mut self) -> Result<u8, ReplayError> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn u32(&mut self) -> Result<u32, ReplayError> {
+        Ok(u32::from_le_bytes(self.take(4)?.try_into().unwrap()))
+    }
+
+    fn u64(&mut self) -> Result<u64, ReplayError> {
+        Ok(u64::from_le_bytes(self.take(8)?.try_into().unwrap()))
+    }
+
+    fn varint(&mut self) -> Result<u32, ReplayError> {
+        let mut value: u32 = 0;
+        for shift in (0..35).step_by(7) {
+            let b = self.u8()?;
+            value |= ((b & 0x7f) as u32) << shift;
+            if b & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+        Err(ReplayError::Truncated)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::simulation::{MatchResult, FIRE, ROTATE_LEFT, THRUST};
+
+    fn scripted_replay() -> Replay {
+        let mut game_state = create_game_state(7, MatchConfig::default());
+        let mut recorder = ReplayRecorder::new(7, MatchConfig::default());
+        for tick in 0..600u32 {
+            let mut input = TickInput::default();
+            if tick % 9 == 0 {
+                input.players[0].press(THRUST);
+            }
+            if tick % 40 < 3 {
+                input.players[1].press(ROTATE_LEFT);
+            }
+            if tick % 50 == 0 {
+                input.players[0].press(FIRE);
+                input.players[1].press(FIRE);
+            }
+            update_game_state(&mut game_state, &input);
+            recorder.record(input, &game_state);
+        }
+        recorder.replay().clone()
+    }
+
+    #[test]
+    fn round_trips_through_bytes() {
+        let replay = scripted_replay();
+        let bytes = replay.to_bytes();
+        assert_eq!(Replay::from_bytes(&bytes), Ok(replay.clone()));
+        // run-length encoding should beat two bytes a tick by a wide margin
+        let header = Replay::new(7, MatchConfig::default()).to_bytes().len();
+        assert!(bytes.len() - header < replay.inputs.len());
+    }
+
+    #[test]
+    fn headless_playback_matches_recording() {
+        let replay = Replay::from_bytes(&scripted_replay().to_bytes()).unwrap();
+        let first = replay.simulate();
+        let second = replay.simulate();
+        assert_eq!(first.tick, replay.tick_count());
+        assert_eq!(state_hash(&first), replay.final_hash);
+        assert_eq!(state_hash(&first), state_hash(&second));
+    }
+
+    #[test]
+    fn seeking_matches_straight_run() {
+        let replay = scripted_replay();
+        let mut straight = replay.initial_state();
+        replay.advance(&mut straight, 450);
+        let mut resumed = replay.initial_state();
+        replay.advance(&mut resumed, 200);
+        replay.advance(&mut resumed, 450);
+        assert_eq!(state_hash(&straight), state_hash(&resumed));
+    }
+
+    #[test]
+    fn torpedo_decides_the_match() {
+        // player one faces player two along the diagonal and fires once the round starts
+        let mut config = MatchConfig::default();
+        config.rules.scoring.best_of = 1;
+        let mut game_state = create_game_state(1, config.clone());
+        for _ in 0..config.rules.round.countdown_ticks {
+            update_game_state(&mut game_state, &TickInput::default());
+        }
+        game_state.players[0].vehicle.vifo_theta = cgmath::Rad(std::f32::consts::PI * 1.25);
+        let mut fire = TickInput::default();
+        fire.players[0].press(FIRE);
+        update_game_state(&mut game_state, &fire);
+        for _ in 0..400 {
+            update_game_state(&mut game_state, &TickInput::default());
+        }
+        assert_eq!(game_state.result, MatchResult::Winner(0));
+        assert!(!game_state.players[1].alive);
+    }
+
+    #[test]
+    fn rejects_damaged_files() {
+        let bytes = scripted_replay().to_bytes();
+        assert_eq!(Replay::from_bytes(&bytes[..bytes.len() - 1]), Err(ReplayError::Truncated));
+        assert_eq!(Replay::from_bytes(b"GIF89a"), Err(ReplayError::BadMagic));
+        let mut future = bytes.clone();
+        future[4] = 99;
+        assert_eq!(Replay::from_bytes(&future), Err(ReplayError::UnsupportedVersion(99)));
+    }
+
+    #[test]
+    fn carries_the_rules_it_was_played_under() {
+        let config = MatchConfig { rules: crate::simulation::presets()[1].clone(), ..MatchConfig::default() };
+        let replay = Replay::new(5, config);
+        assert_eq!(Replay::from_bytes(&replay.to_bytes()), Ok(replay));
+    }
+
+    #[test]
+    fn version_one_configs_load_under_the_classic_rules() {
+        let config = br#"{ "mode": "VersusComputer", "best_of": 3, "round_ticks": 900 }"#;
+        let mut bytes = Replay::new(5, MatchConfig::default()).to_bytes();
+        bytes[4] = 1;
+        let old_len = u32::from_le_bytes(bytes[13..17].try_into().unwrap()) as usize;
+        bytes.splice(13..17 + old_len, (config.len() as u32).to_le_bytes().into_iter().chain(config.iter().copied()));
+
+        let replay = Replay::from_bytes(&bytes).unwrap();
+        assert_eq!(replay.config.mode, GameMode::VersusComputer);
+        assert_eq!(replay.config.rules.scoring.best_of, 3);
+        assert_eq!(replay.config.rules.round.round_ticks, 900);
+        assert_eq!(replay.config.rules.ship, MatchConfig::default().rules.ship);
+    }
+
+    #[test]
+    fn rejects_rules_out_of_range() {
+        let mut config = MatchConfig::default();
+        config.rules.torpedo.speed = 0.0;
+        let bytes = Replay::new(5, config).to_bytes();
+        assert!(matches!(Replay::from_bytes(&bytes), Err(ReplayError::BadConfig(e)) if e.contains("torpedo.speed")));
+    }
+}
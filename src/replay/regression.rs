@@ -0,0 +1,114 @@
+// Replay regression suite.
+//
+// Every `.swrp` file under tests/replays is run through the headless simulation and
+// its final tick count, winner and state hash are compared with tests/replays/goldens.json.
+// Any change to the rules that alters how a recorded match plays out shows up here.  The
+// match_* replays, the computer flying both ships under each preset, run to the end of
+// their matches, so the winner is checked as well.
+//
+// When a change is intentional, re-bless the goldens and commit the new file:
+//
+//     cargo test bless_replay_goldens -- --ignored
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use super::Replay;
+use crate::simulation::{state_hash, MatchResult, PLAYER_ONE, PLAYER_TWO};
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Golden {
+    ticks: u32,
+    winner: String,
+    // hex, since JSON numbers can't hold a u64 exactly
+    state_hash: String,
+}
+
+fn corpus_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("tests").join("replays")
+}
+
+fn goldens_path() -> PathBuf {
+    corpus_dir().join("goldens.json")
+}
+
+fn winner(result: MatchResult) -> String {
+    match result {
+        MatchResult::Undecided => String::from("undecided"),
+        MatchResult::Winner(PLAYER_ONE) => String::from("player one"),
+        MatchResult::Winner(PLAYER_TWO) => String::from("player two"),
+        MatchResult::Winner(idx) => format!("player {}", idx + 1),
+        MatchResult::Draw => String::from("draw"),
+    }
+}
+
+fn run_corpus() -> BTreeMap<String, Golden> {
+    let mut paths: Vec<PathBuf> = fs::read_dir(corpus_dir())
+        .expect("tests/replays is readable")
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == super::FILE_EXTENSION))
+        .collect();
+    paths.sort();
+
+    paths
+        .into_iter()
+        .map(|path| {
+            let name = path.file_name().unwrap().to_string_lossy().into_owned();
+            let bytes = fs::read(&path).unwrap();
+            let replay = Replay::from_bytes(&bytes)
+                .unwrap_or_else(|e| panic!("{} does not load: {}", name, e));
+            let game_state = replay.simulate();
+            let golden = Golden {
+                ticks: game_state.tick,
+                winner: winner(game_state.result),
+                state_hash: format!("{:016x}", state_hash(&game_state)),
+            };
+            (name, golden)
+        })
+        .collect()
+}
+
+#[test]
+fn replays_match_goldens() {
+    let goldens = fs::read_to_string(goldens_path()).expect("tests/replays/goldens.json exists");
+    let mut expected: BTreeMap<String, Golden> = serde_json::from_str(&goldens).unwrap();
+    let actual = run_corpus();
+    assert!(!actual.is_empty(), "no replays found in {}", corpus_dir().display());
+    // or the winner, and how a match ends, would go unchecked
+    assert!(
+        actual.values().any(|golden| golden.winner != winner(MatchResult::Undecided)),
+        "no replay plays its match to the end"
+    );
+
+    let mut failures: Vec<String> = vec![];
+    for (name, got) in actual.iter() {
+        match expected.remove(name) {
+            None => failures.push(format!("{}: no golden recorded", name)),
+            Some(want) if want != *got => failures.push(format!(
+                "{}:\n    expected {:?}\n    got      {:?}",
+                name, want, got
+            )),
+            Some(_) => (),
+        }
+    }
+    for name in expected.keys() {
+        failures.push(format!("{}: golden recorded but the replay is missing", name));
+    }
+
+    assert!(
+        failures.is_empty(),
+        "replay goldens differ:\n{}\n\nIf the rules changed on purpose, re-bless with\n    cargo test bless_replay_goldens -- --ignored\n",
+        failures.join("\n")
+    );
+}
+
+#[test]
+#[ignore]
+fn bless_replay_goldens() {
+    let goldens = serde_json::to_string_pretty(&run_corpus()).unwrap();
+    fs::write(goldens_path(), goldens + "\n").unwrap();
+}
+
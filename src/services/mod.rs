@@ -1,2 +1,5 @@
+// Only the chat uses these, and it's switched off in components/mod.rs.
+#[allow(dead_code)]
 pub mod websocket;
+#[allow(dead_code)]
 pub mod event_bus;
@@ -0,0 +1,45 @@
+// Keeping the settings in the browser's localStorage, and reading rules files the player
+// picks.
+
+use gloo_console::log;
+use wasm_bindgen_futures::JsFuture;
+use web_sys::{File, Storage};
+
+use super::Settings;
+use crate::simulation::GameRules;
+
+const STORAGE_KEY: &str = "spacewar.settings";
+
+fn storage() -> Option<Storage> {
+    web_sys::window()?.local_storage().ok()?
+}
+
+// The stored settings, or the defaults when there are none, they can't be read or they
+// hold values out of range.  Bad ones stay in storage only until the next change on the
+// settings page saves over them.
+pub fn load_settings() -> Settings {
+    let json = match storage().and_then(|s| s.get_item(STORAGE_KEY).ok()?) {
+        Some(json) => json,
+        None => return Settings::default(),
+    };
+    Settings::from_json(&json).unwrap_or_else(|e| {
+        log!("using default settings:", e.to_string());
+        Settings::default()
+    })
+}
+
+pub fn save_settings(settings: &Settings) {
+    let saved = storage().map(|s| s.set_item(STORAGE_KEY, &settings.to_json()));
+    if !matches!(saved, Some(Ok(()))) {
+        log!("settings could not be saved; they'll last until the page is closed");
+    }
+}
+
+// Read a rules file picked from an <input type="file">.
+pub async fn read_rules(file: File) -> Result<GameRules, String> {
+    let text = JsFuture::from(file.text())
+        .await
+        .map_err(|e| format!("could not read {}: {:?}", file.name(), e))?;
+    let text = text.as_string().unwrap_or_default();
+    GameRules::parse(&file.name(), &text).map_err(|e| format!("{}: {}", file.name(), e))
+}
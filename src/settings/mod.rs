@@ -0,0 +1,282 @@
+// The player's settings: keys, sound, graphics, colours, names and the rules a new game
+// starts with.  They're kept in the browser (browser.rs) as JSON with a schema version:
+//
+//     { "version": SCHEMA_VERSION, "settings": { ... } }
+//
+// Adding a field needs nothing more than a default, since anything missing from a stored
+// copy is filled in from `Settings::default`.  Renaming or reshaping one bumps
+// SCHEMA_VERSION and adds a step to MIGRATIONS that rewrites the old JSON into the new
+// shape, so nobody's settings are lost on upgrade.  The rules are checked like a rules
+// file, and the volume and particle density against their ranges, so a hand-edited copy
+// can't hand the game values it was never meant to see.
+
+pub mod browser;
+
+use std::fmt;
+use std::ops::RangeInclusive;
+use std::rc::Rc;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use yew::Callback;
+
+use crate::renderer::{Palette, PostSettings, StarChart};
+use crate::simulation::rules::RulesError;
+use crate::simulation::{MatchConfig, FIRE, HYPERSPACE, ROTATE_LEFT, ROTATE_RIGHT, THRUST};
+
+// A step from one schema version to the next: MIGRATIONS[n] turns version n + 1 into
+// n + 2.
+type Migration = fn(&mut Value);
+// Empty while there has only ever been version 1.
+const MIGRATIONS: [Migration; 0] = [];
+
+pub const SCHEMA_VERSION: u32 = MIGRATIONS.len() as u32 + 1;
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    // player one's, then player two's
+    pub keys: [PlayerKeys; 2],
+    // 0 to 1.  Nothing in the game makes a sound yet.
+    pub volume: f32,
+    pub graphics: Graphics,
+    pub palette: Palette,
+    pub names: [String; 2],
+    // ships and rules for every new game; the menu picks the mode
+    pub match_config: MatchConfig,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            keys: [
+                PlayerKeys { rotate_left: 37, rotate_right: 39, thrust: 38, fire: 96, hyperspace: 40 },
+                // ';', 'j', 'o', space and 'q' on a Dvorak layout; see the README
+                PlayerKeys { rotate_left: 186, rotate_right: 74, thrust: 79, fire: 32, hyperspace: 81 },
+            ],
+            volume: 0.8,
+            graphics: Graphics::default(),
+            palette: Palette::default(),
+            names: default_names(),
+            match_config: MatchConfig::default(),
+        }
+    }
+}
+
+pub fn default_names() -> [String; 2] {
+    [String::from("Player 1"), String::from("Player 2")]
+}
+
+// Key codes, as `KeyboardEvent::key_code` gives them.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PlayerKeys {
+    pub rotate_left: u32,
+    pub rotate_right: u32,
+    pub thrust: u32,
+    pub fire: u32,
+    pub hyperspace: u32,
+}
+
+impl PlayerKeys {
+    // each key with the control bit it presses
+    pub fn controls(&self) -> [(u32, u8); 5] {
+        [
+            (self.rotate_left, ROTATE_LEFT),
+            (self.rotate_right, ROTATE_RIGHT),
+            (self.thrust, THRUST),
+            (self.fire, FIRE),
+            (self.hyperspace, HYPERSPACE),
+        ]
+    }
+
+    // Puts `control` on `code`.
+    pub fn bind(&mut self, control: u8, code: u32) {
+        match control {
+            ROTATE_LEFT => self.rotate_left = code,
+            ROTATE_RIGHT => self.rotate_right = code,
+            THRUST => self.thrust = code,
+            FIRE => self.fire = code,
+            HYPERSPACE => self.hyperspace = code,
+            _ => (),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Graphics {
+    pub post: PostSettings,
+    pub star_chart: StarChart,
+    // how many particles each effect throws out, against the usual
+    pub particles: f32,
+}
+
+impl Default for Graphics {
+    fn default() -> Self {
+        Self { post: PostSettings::default(), star_chart: StarChart::default(), particles: 1.0 }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum SettingsError {
+    Malformed(String),
+    // written by a later version of the game, which may have meant anything by it
+    NewerVersion(u32),
+    BadRules(RulesError),
+    OutOfRange { field: &'static str, value: f64, allowed: RangeInclusive<f64> },
+}
+
+impl fmt::Display for SettingsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SettingsError::Malformed(e) => write!(f, "stored settings are malformed: {}", e),
+            SettingsError::NewerVersion(v) => {
+                write!(f, "stored settings are version {}, newer than this game's {}", v, SCHEMA_VERSION)
+            }
+            SettingsError::BadRules(e) => write!(f, "stored rules are invalid: {}", e),
+            SettingsError::OutOfRange { field, value, allowed } => write!(
+                f,
+                "stored {} is {}, but must be from {} to {}",
+                field,
+                value,
+                allowed.start(),
+                allowed.end()
+            ),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct Stored {
+    version: u32,
+    settings: Value,
+}
+
+impl Settings {
+    pub fn to_json(&self) -> String {
+        let stored = Stored {
+            version: SCHEMA_VERSION,
+            settings: serde_json::to_value(self).expect("settings always serialize"),
+        };
+        serde_json::to_string(&stored).expect("settings always serialize")
+    }
+
+    pub fn from_json(json: &str) -> Result<Self, SettingsError> {
+        from_json_with(json, &MIGRATIONS)
+    }
+}
+
+fn from_json_with(json: &str, migrations: &[Migration]) -> Result<Settings, SettingsError> {
+    let malformed = |e: serde_json::Error| SettingsError::Malformed(e.to_string());
+    let Stored { version, mut settings } = serde_json::from_str(json).map_err(malformed)?;
+    let latest = migrations.len() as u32 + 1;
+    if version > latest {
+        return Err(SettingsError::NewerVersion(version));
+    }
+    if version == 0 {
+        return Err(SettingsError::Malformed(String::from("version 0")));
+    }
+    for migrate in &migrations[version as usize - 1..] {
+        migrate(&mut settings);
+    }
+    let settings: Settings = serde_json::from_value(settings).map_err(malformed)?;
+    check("volume", settings.volume, 0.0..=1.0)?;
+    check("graphics.particles", settings.graphics.particles, 0.1..=4.0)?;
+    settings.match_config.rules.validate().map_err(SettingsError::BadRules)?;
+    Ok(settings)
+}
+
+fn check(field: &'static str, value: f32, allowed: RangeInclusive<f64>) -> Result<(), SettingsError> {
+    let value = value as f64;
+    match allowed.contains(&value) {
+        true => Ok(()),
+        false => Err(SettingsError::OutOfRange { field, value, allowed }),
+    }
+}
+
+// What components get from the settings context: the settings, and how to change them.
+// Changes are saved as they're made.
+#[derive(Clone, PartialEq)]
+pub struct SettingsContext {
+    pub settings: Rc<Settings>,
+    pub update: Callback<Settings>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::simulation::ShipDesign;
+
+    #[test]
+    fn settings_survive_the_round_trip() {
+        let mut settings = Settings::default();
+        settings.names[1] = String::from("Computer");
+        settings.keys[0].fire = 17;
+        settings.palette = Palette::Contrast;
+        settings.graphics.particles = 0.5;
+        settings.match_config.ships = [ShipDesign::Dart, ShipDesign::Dart];
+        settings.match_config.rules = crate::simulation::presets()[2].clone();
+        assert_eq!(Settings::from_json(&settings.to_json()), Ok(settings));
+    }
+
+    #[test]
+    fn anything_missing_takes_its_default() {
+        let json = r#"{ "version": 1, "settings": { "volume": 0.25, "graphics": { "particles": 2.0 } } }"#;
+        let settings = Settings::from_json(json).unwrap();
+        assert_eq!(settings.volume, 0.25);
+        assert_eq!(settings.graphics.particles, 2.0);
+        assert_eq!(settings.graphics.post, PostSettings::default());
+        assert_eq!(settings.keys, Settings::default().keys);
+    }
+
+    #[test]
+    fn older_settings_are_migrated_step_by_step() {
+        // version 1 called it "loudness" out of 10; version 2 "volume" out of 1, and
+        // version 3 halved everyone's
+        let migrations: [Migration; 2] = [
+            |v| {
+                let loudness = v["loudness"].as_f64().unwrap();
+                v["volume"] = Value::from(loudness / 10.0);
+            },
+            |v| v["volume"] = Value::from(v["volume"].as_f64().unwrap() / 2.0),
+        ];
+        let json = r#"{ "version": 1, "settings": { "loudness": 6 } }"#;
+        assert_eq!(from_json_with(json, &migrations).unwrap().volume, 0.3);
+        let json = r#"{ "version": 2, "settings": { "volume": 0.6 } }"#;
+        assert_eq!(from_json_with(json, &migrations).unwrap().volume, 0.3);
+    }
+
+    #[test]
+    fn newer_or_broken_settings_are_refused() {
+        let json = format!(r#"{{ "version": {}, "settings": {{}} }}"#, SCHEMA_VERSION + 1);
+        assert_eq!(Settings::from_json(&json), Err(SettingsError::NewerVersion(SCHEMA_VERSION + 1)));
+        assert!(matches!(Settings::from_json("{ not json"), Err(SettingsError::Malformed(_))));
+        let json = r#"{ "version": 1, "settings": { "volume": "loud" } }"#;
+        assert!(matches!(Settings::from_json(json), Err(SettingsError::Malformed(_))));
+    }
+
+    #[test]
+    fn stored_rules_are_checked_like_a_rules_file() {
+        let mut settings = Settings::default();
+        settings.match_config.rules.torpedo.speed = 50.0;
+        let error = Settings::from_json(&settings.to_json()).unwrap_err();
+        assert!(matches!(error, SettingsError::BadRules(RulesError::OutOfRange { field: "torpedo.speed", .. })));
+    }
+
+    #[test]
+    fn volume_and_particles_are_kept_in_range() {
+        for (json, field) in [
+            (r#"{ "volume": 1.5 }"#, "volume"),
+            (r#"{ "volume": -0.1 }"#, "volume"),
+            (r#"{ "graphics": { "particles": 0 } }"#, "graphics.particles"),
+            (r#"{ "graphics": { "particles": -1 } }"#, "graphics.particles"),
+            (r#"{ "graphics": { "particles": 1000 } }"#, "graphics.particles"),
+        ] {
+            let json = format!(r#"{{ "version": 1, "settings": {} }}"#, json);
+            let error = Settings::from_json(&json).unwrap_err();
+            assert!(matches!(error, SettingsError::OutOfRange { field: f, .. } if f == field), "{}", json);
+        }
+        // JSON has no NaN, but one that got past it would be refused all the same
+        assert!(matches!(check("graphics.particles", f32::NAN, 0.1..=4.0), Err(SettingsError::OutOfRange { .. })));
+    }
+}
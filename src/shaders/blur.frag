@@ -0,0 +1,29 @@
+#version 300 es
+
+precision mediump float;
+
+in vec2 v_uv;
+
+uniform sampler2D u_image;
+// one texel along the direction of this pass
+uniform vec2 u_step;
+// only what's brighter than this glows; 0 after the first pass
+uniform float u_threshold;
+
+out vec4 FragColor;
+
+// one side of a 9-tap gaussian
+const float WEIGHTS[5] = float[](0.227027, 0.1945946, 0.1216216, 0.054054, 0.016216);
+
+vec3 bright(vec2 uv) {
+    return max(texture(u_image, uv).rgb - u_threshold, 0.0);
+}
+
+void main() {
+    vec3 sum = bright(v_uv) * WEIGHTS[0];
+    for (int i = 1; i < 5; i++) {
+        vec2 offset = u_step * float(i);
+        sum += (bright(v_uv + offset) + bright(v_uv - offset)) * WEIGHTS[i];
+    }
+    FragColor = vec4(sum, 1.0);
+}
@@ -0,0 +1,38 @@
+#version 300 es
+
+precision mediump float;
+
+in vec2 v_uv;
+
+uniform sampler2D u_image;
+uniform sampler2D u_bloom;
+// 0 for no glow
+uniform float u_bloom_strength;
+// 0 for a flat screen
+uniform float u_curvature;
+// lines across the arena, 0 for none
+uniform float u_scanlines;
+
+out vec4 FragColor;
+
+const float SCANLINE_DEPTH = 0.35;
+
+void main() {
+    // Barrel distortion, scaled so the middle of each edge stays put and the corners
+    // fall off the tube.
+    vec2 p = v_uv * 2.0 - 1.0;
+    p *= (1.0 + u_curvature * dot(p, p)) / (1.0 + u_curvature);
+    vec2 uv = p * 0.5 + 0.5;
+    if (any(lessThan(uv, vec2(0.0))) || any(greaterThan(uv, vec2(1.0)))) {
+        FragColor = vec4(0.0, 0.0, 0.0, 1.0);
+        return;
+    }
+
+    vec3 color = texture(u_image, uv).rgb + texture(u_bloom, uv).rgb * u_bloom_strength;
+    if (u_scanlines > 0.0) {
+        // following the curve, like the real thing
+        float gap = 0.5 - 0.5 * cos(uv.y * u_scanlines * 6.2831853);
+        color *= 1.0 - SCANLINE_DEPTH * gap;
+    }
+    FragColor = vec4(color, 1.0);
+}
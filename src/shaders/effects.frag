@@ -0,0 +1,16 @@
+#version 300 es
+
+precision mediump float;
+
+in vec4 v_color;
+in float v_fade;
+
+out vec4 FragColor;
+
+void main() {
+    if (v_fade <= 0.0) {
+        discard;
+    }
+    float alpha = v_color.a * v_fade;
+    FragColor = vec4(v_color.rgb * alpha, alpha);
+}
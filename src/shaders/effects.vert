@@ -0,0 +1,64 @@
+#version 300 es
+
+precision mediump float;
+
+// One particle per vertex.  Each frame this both draws the particle and, through
+// transform feedback, writes its advanced state into the other buffer.
+layout(location = 0) in vec2 a_position;
+layout(location = 1) in vec2 a_velocity;
+layout(location = 2) in float a_age;
+layout(location = 3) in float a_lifetime;
+layout(location = 4) in vec4 a_color;
+
+layout(std140) uniform Camera {
+    mat4 u_projection;
+};
+
+// xyz position, w mass; see renderer/gravity.rs
+#define MAX_ATTRACTORS 64
+layout(std140) uniform Gravity {
+    int u_attractor_count;
+    vec4 u_attractors[MAX_ATTRACTORS];
+};
+
+uniform float u_delta;
+uniform float u_point_size;
+
+// captured by transform feedback
+out vec2 v_position;
+out vec2 v_velocity;
+out float v_age;
+out float v_lifetime;
+out vec4 v_color;
+// for the fragment shader only
+out float v_fade;
+
+// fraction of velocity kept after one second
+const float DRAG = 0.3;
+
+vec2 gravity(vec2 position) {
+    vec2 acceleration = vec2(0.0);
+    for (int i = 0; i < u_attractor_count; i++) {
+        vec2 offset = u_attractors[i].xy - position;
+        float dist2 = dot(offset, offset);
+        // nothing to fall towards when sitting right on the mass
+        if (dist2 > 0.0) {
+            acceleration += u_attractors[i].w * offset * inversesqrt(dist2) / max(0.01, dist2);
+        }
+    }
+    return acceleration;
+}
+
+void main() {
+    vec2 velocity = a_velocity + gravity(a_position) * u_delta;
+    v_position = a_position + velocity * u_delta;
+    v_velocity = velocity * pow(DRAG, u_delta);
+    v_age = a_age + u_delta;
+    v_lifetime = a_lifetime;
+    v_color = a_color;
+
+    // never-used slots have a zero lifetime
+    v_fade = a_lifetime > 0.0 ? clamp(1.0 - a_age / a_lifetime, 0.0, 1.0) : 0.0;
+    gl_PointSize = v_fade > 0.0 ? u_point_size : 0.0;
+    gl_Position = u_projection * vec4(a_position, 0.0, 1.0);
+}
@@ -0,0 +1,51 @@
+#version 300 es
+
+precision highp float;
+
+in vec2 v_world;
+
+// picks this match's clouds out of the noise
+uniform vec2 u_offset;
+
+out vec4 FragColor;
+
+float hash(vec2 cell) {
+    return fract(sin(dot(cell + u_offset, vec2(127.1, 311.7))) * 43758.5453);
+}
+
+// Value noise on a lattice that repeats every `period` cells.  Sampled at one period
+// across the arena, the left edge matches the right and the top the bottom, so the
+// clouds wrap with the ships.
+float noise(vec2 p, float period) {
+    vec2 i = floor(p);
+    vec2 f = fract(p);
+    vec2 u = f * f * (3.0 - 2.0 * f);
+    float a = hash(mod(i, period));
+    float b = hash(mod(i + vec2(1.0, 0.0), period));
+    float c = hash(mod(i + vec2(0.0, 1.0), period));
+    float d = hash(mod(i + vec2(1.0, 1.0), period));
+    return mix(mix(a, b, u.x), mix(c, d, u.x), u.y);
+}
+
+float fbm(vec2 uv, float period) {
+    float total = 0.0;
+    float amplitude = 0.5;
+    for (int octave = 0; octave < 4; octave++) {
+        total += amplitude * noise(uv * period, period);
+        period *= 2.0;
+        amplitude *= 0.5;
+    }
+    return total;
+}
+
+const vec3 VIOLET = vec3(0.25, 0.08, 0.35);
+const vec3 TEAL = vec3(0.05, 0.18, 0.35);
+const vec3 SPACE = vec3(0.0, 0.0, 0.02);
+
+void main() {
+    // 0..1 across the arena
+    vec2 uv = v_world * 0.5 + 0.5;
+    float density = smoothstep(0.45, 0.85, fbm(uv, 3.0));
+    vec3 cloud = mix(VIOLET, TEAL, fbm(uv + 0.37, 2.0));
+    FragColor = vec4(SPACE + cloud * density * 0.35, 1.0);
+}
@@ -0,0 +1,17 @@
+#version 300 es
+
+precision mediump float;
+
+// a quad covering the arena
+layout(location = 0) in vec2 a_position;
+
+layout(std140) uniform Camera {
+    mat4 u_projection;
+};
+
+out vec2 v_world;
+
+void main() {
+    v_world = a_position;
+    gl_Position = u_projection * vec4(a_position, 0.0, 1.0);
+}
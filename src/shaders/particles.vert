@@ -6,13 +6,11 @@ layout(location=0) in vec3 aPosition;
 layout(location=1) in vec3 aVelocity;
 layout(location=2) in vec3 aColor;
 
-uniform Mass {
-    float mass1Factor;
-    float mass2Factor;
-    float mass3Factor;
-    vec4 mass1Position;
-    vec4 mass2Position;
-    vec4 mass3Position;
+// xyz position, w mass; see renderer/gravity.rs
+#define MAX_ATTRACTORS 64
+uniform Gravity {
+    int uAttractorCount;
+    vec4 uAttractors[MAX_ATTRACTORS];
 };
 
 out vec3 vPosition;
@@ -22,17 +20,15 @@ void main() {
     vec3 position = aPosition;
     vec3 velocity = aVelocity;
 
-    vec3 massVec = mass1Position.xyz - position;
-    float massDist2 = max(0.01, dot(massVec, massVec));
-    vec3 acceleration = mass1Factor * normalize(massVec) / massDist2;
-
-    massVec = mass2Position.xyz - position;
-    massDist2 = max(0.01, dot(massVec, massVec));
-    acceleration += mass2Factor * normalize(massVec) / massDist2;
-
-    massVec = mass3Position.xyz - position;
-    massDist2 = max(0.01, dot(massVec, massVec));
-    acceleration += mass3Factor * normalize(massVec) / massDist2;
+    vec3 acceleration = vec3(0.0);
+    for (int i = 0; i < uAttractorCount; i++) {
+        vec3 massVec = uAttractors[i].xyz - position;
+        float massDist2 = dot(massVec, massVec);
+        // a particle sitting right on a mass has no direction to fall in
+        if (massDist2 > 0.0) {
+            acceleration += uAttractors[i].w * massVec * inversesqrt(massDist2) / max(0.01, massDist2);
+        }
+    }
 
     velocity += acceleration;
     velocity *= 0.9999;
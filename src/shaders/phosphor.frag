@@ -0,0 +1,23 @@
+#version 300 es
+
+precision mediump float;
+
+in vec2 v_uv;
+
+uniform sampler2D u_scene;
+// last frame's glow
+uniform sampler2D u_history;
+// how much of it survives this frame
+uniform float u_decay;
+
+out vec4 FragColor;
+
+// The targets are 8 bits a channel, where v * decay rounds back up to v once v is small
+// enough; taking a little off the top as well lets the faintest trails clear.
+const float FLOOR = 1.5 / 255.0;
+
+void main() {
+    vec3 scene = texture(u_scene, v_uv).rgb;
+    vec3 fading = max(texture(u_history, v_uv).rgb * u_decay - FLOOR, 0.0);
+    FragColor = vec4(max(scene, fading), 1.0);
+}
@@ -0,0 +1,11 @@
+#version 300 es
+
+// a quad covering whatever is bound, in clip space
+layout(location = 0) in vec2 a_position;
+
+out vec2 v_uv;
+
+void main() {
+    v_uv = a_position * 0.5 + 0.5;
+    gl_Position = vec4(a_position, 0.0, 1.0);
+}
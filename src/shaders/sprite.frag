@@ -0,0 +1,11 @@
+#version 300 es
+
+precision mediump float;
+
+in vec4 v_color;
+
+out vec4 FragColor;
+
+void main() {
+    FragColor = v_color;
+}
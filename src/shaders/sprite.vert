@@ -0,0 +1,30 @@
+#version 300 es
+
+precision mediump float;
+
+// per vertex
+layout(location = 0) in vec2 a_position;
+// per instance
+layout(location = 1) in vec2 i_position;
+layout(location = 2) in float i_rotation;
+layout(location = 3) in vec4 i_color;
+
+layout(std140) uniform Camera {
+    mat4 u_projection;
+};
+
+out vec4 v_color;
+
+mat2 r2d(float a) {
+	float c = cos(a), s = sin(a);
+    return mat2(
+        c, s, // column 1
+        -s, c // column 2
+    );
+}
+
+void main() {
+    vec2 p = r2d(i_rotation) * a_position + i_position;
+    gl_Position = u_projection * vec4(p, 0.0, 1.0);
+    v_color = i_color;
+}
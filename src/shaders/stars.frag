@@ -0,0 +1,16 @@
+#version 300 es
+
+precision mediump float;
+
+in float v_brightness;
+
+out vec4 FragColor;
+
+const vec3 STAR_COLOR = vec3(0.9, 0.95, 1.0);
+
+void main() {
+    // soft round points rather than squares
+    float d = length(gl_PointCoord - 0.5) * 2.0;
+    float alpha = v_brightness * (1.0 - smoothstep(0.4, 1.0, d));
+    FragColor = vec4(STAR_COLOR * alpha, alpha);
+}
@@ -0,0 +1,31 @@
+#version 300 es
+
+precision mediump float;
+
+// One star per point, all drawn in a single call behind everything else.
+layout(location = 0) in vec2 a_position;
+layout(location = 1) in float a_brightness;
+layout(location = 2) in float a_size;
+layout(location = 3) in float a_twinkle;
+
+layout(std140) uniform Camera {
+    mat4 u_projection;
+};
+
+// seconds; grows without bound, hence highp
+uniform highp float u_time;
+// the arena's side in pixels
+uniform float u_point_scale;
+
+out float v_brightness;
+
+const float TAU = 6.2831853;
+
+void main() {
+    // every star on its own slow beat, dimming by up to a third
+    float beat = sin(u_time * (0.8 + 1.6 * a_twinkle) + a_twinkle * TAU);
+    v_brightness = a_brightness * (1.0 - 0.33 * (0.5 + 0.5 * beat));
+
+    gl_PointSize = max(1.0, a_size * u_point_scale);
+    gl_Position = u_projection * vec4(a_position, 0.0, 1.0);
+}
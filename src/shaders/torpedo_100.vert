@@ -4,7 +4,7 @@ precision mediump float;
 uniform vec2 pos_deltas;
 uniform float vifo_theta;
 
-in vec2 b_position;
+layout(location = 0) in vec2 b_position;
 
 
 mat2 r2d(float a) {
@@ -2,7 +2,7 @@
 
 precision mediump float;
 
-in vec2 a_position; 
+layout(location = 0) in vec2 a_position;
 uniform vec2 pos_deltas;
 uniform float vifo_theta;
 
@@ -0,0 +1,172 @@
+// Ship designs and the little vector format they're drawn in.
+//
+// A `.vmesh` file is lines of text; `#` starts a comment:
+//
+//     name Wedge
+//     v 18 0          a vertex, x then y
+//     t 0 1 2         a triangle, by vertex number from 0
+//
+// Coordinates are in thousandths of a world unit (the arena is 2000 across), with the
+// nose along +x.  The renderer draws the triangles; the simulation collides with the
+// convex hull of the vertices, so a design's hull always matches what's on screen.
+
+use std::fmt;
+use std::sync::OnceLock;
+
+use serde::{Deserialize, Serialize};
+
+use super::hull::Hull;
+
+const MESH_UNIT: f32 = 0.001;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ShipDesign {
+    Dart,
+    Needle,
+    Wedge,
+}
+
+impl ShipDesign {
+    pub const ALL: [ShipDesign; 3] = [ShipDesign::Dart, ShipDesign::Needle, ShipDesign::Wedge];
+
+    fn source(&self) -> &'static str {
+        match self {
+            ShipDesign::Dart => include_str!("../meshes/dart.vmesh"),
+            ShipDesign::Needle => include_str!("../meshes/needle.vmesh"),
+            ShipDesign::Wedge => include_str!("../meshes/wedge.vmesh"),
+        }
+    }
+
+    pub fn mesh(&self) -> &'static VectorMesh {
+        &designs()[*self as usize].0
+    }
+
+    pub fn hull(&self) -> &'static Hull {
+        &designs()[*self as usize].1
+    }
+}
+
+// parsed once, in ALL order
+fn designs() -> &'static [(VectorMesh, Hull)] {
+    static DESIGNS: OnceLock<Vec<(VectorMesh, Hull)>> = OnceLock::new();
+    DESIGNS.get_or_init(|| {
+        ShipDesign::ALL
+            .iter()
+            .map(|design| {
+                let mesh = VectorMesh::parse(design.source())
+                    .unwrap_or_else(|e| panic!("{:?} mesh is broken: {}", design, e));
+                let hull = Hull::convex(&mesh.vertices);
+                (mesh, hull)
+            })
+            .collect()
+    })
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct VectorMesh {
+    pub name: String,
+    // world units
+    pub vertices: Vec<[f32; 2]>,
+    pub triangles: Vec<[usize; 3]>,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct MeshError {
+    pub line: usize,
+    pub message: String,
+}
+
+impl fmt::Display for MeshError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl VectorMesh {
+    pub fn parse(source: &str) -> Result<Self, MeshError> {
+        let mut mesh = VectorMesh { name: String::new(), vertices: vec![], triangles: vec![] };
+
+        for (idx, line) in source.lines().enumerate() {
+            let error = |message: String| MeshError { line: idx + 1, message };
+            let line = line.split('#').next().unwrap_or("");
+            let mut words = line.split_whitespace();
+            let keyword = match words.next() {
+                Some(keyword) => keyword,
+                None => continue,
+            };
+            let args: Vec<&str> = words.collect();
+
+            match (keyword, args.len()) {
+                ("name", n) if n > 0 => mesh.name = args.join(" "),
+                ("v", 2) => {
+                    let mut vertex = [0.0; 2];
+                    for (slot, arg) in vertex.iter_mut().zip(args.iter()) {
+                        let value: f32 = arg.parse().map_err(|_| error(format!("bad coordinate {:?}", arg)))?;
+                        *slot = value * MESH_UNIT;
+                    }
+                    mesh.vertices.push(vertex);
+                }
+                ("t", 3) => {
+                    let mut triangle = [0; 3];
+                    for (slot, arg) in triangle.iter_mut().zip(args.iter()) {
+                        *slot = arg
+                            .parse()
+                            .ok()
+                            .filter(|i| *i < mesh.vertices.len())
+                            .ok_or_else(|| error(format!("no vertex {:?} yet", arg)))?;
+                    }
+                    mesh.triangles.push(triangle);
+                }
+                ("name", _) | ("v", _) | ("t", _) => {
+                    return Err(error(format!("wrong number of values for {:?}", keyword)))
+                }
+                _ => return Err(error(format!("unknown keyword {:?}", keyword))),
+            }
+        }
+
+        if mesh.triangles.is_empty() {
+            return Err(MeshError { line: source.lines().count(), message: String::from("no triangles") });
+        }
+        Ok(mesh)
+    }
+
+    // Triangles as a flat list of x, y pairs, ready for a vertex buffer.
+    pub fn triangle_list(&self) -> Vec<f32> {
+        self.triangles
+            .iter()
+            .flat_map(|triangle| triangle.iter())
+            .flat_map(|idx| self.vertices[*idx])
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_design_loads() {
+        for design in ShipDesign::ALL {
+            let mesh = design.mesh();
+            assert_eq!(mesh.name, format!("{:?}", design));
+            assert!(design.hull().points.len() >= 3);
+        }
+    }
+
+    #[test]
+    fn hull_follows_the_mesh() {
+        // the wedge's notch is inside its hull
+        let hull = ShipDesign::Wedge.hull();
+        assert_eq!(hull.points.len(), 3);
+        assert!(!hull.points.contains(&[-0.005, 0.0]));
+        assert!((hull.radius - 0.018).abs() < 1e-6);
+    }
+
+    #[test]
+    fn parse_reports_the_line() {
+        let err = VectorMesh::parse("name Bad\nv 0 0\nv 1 0\nt 0 1 2\n").unwrap_err();
+        assert_eq!(err, MeshError { line: 4, message: String::from("no vertex \"2\" yet") });
+        let err = VectorMesh::parse("# nothing\nq 1\n").unwrap_err();
+        assert_eq!(err.line, 2);
+    }
+}
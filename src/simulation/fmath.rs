@@ -0,0 +1,31 @@
+// Trig used by the simulation.  f32::sin/cos go through the platform libm, which is
+// not guaranteed to give bit-identical results in the browser and on the native
+// test runner, and a replay recorded in one has to reproduce exactly in the other.
+// Everything here is plain IEEE add/mul/round, so it is.
+
+use std::f32::consts::{FRAC_PI_2, PI, TAU};
+
+pub fn sin(theta: f32) -> f32 {
+    // reduce to [-pi, pi], then fold onto [-pi/2, pi/2] where the series converges fast
+    let mut x = theta - (theta / TAU).round() * TAU;
+    if x > FRAC_PI_2 {
+        x = PI - x;
+    } else if x < -FRAC_PI_2 {
+        x = -PI - x;
+    }
+    let x2 = x * x;
+    // Taylor series to x^11, error < 6e-8 on the folded range
+    x * (1.0
+        - x2 / 6.0
+            * (1.0
+                - x2 / 20.0
+                    * (1.0 - x2 / 42.0 * (1.0 - x2 / 72.0 * (1.0 - x2 / 110.0)))))
+}
+
+pub fn cos(theta: f32) -> f32 {
+    sin(theta + FRAC_PI_2)
+}
+
+pub fn sin_cos(theta: f32) -> (f32, f32) {
+    (sin(theta), cos(theta))
+}
@@ -0,0 +1,131 @@
+// Convex collision hulls.  A ship's hull is the convex hull of its mesh, in the ship's
+// own frame (nose along +x); `place` turns it into world coordinates for a tick's tests.
+// Only add, multiply and compare here, plus `fmath`, so results are the same everywhere.
+
+use super::fmath;
+use super::vehicle::Vehicle_100;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Hull {
+    // counter-clockwise, no three in a line
+    pub points: Vec<[f32; 2]>,
+    // of the farthest point from the origin, for a quick rejection first
+    pub radius: f32,
+}
+
+impl Hull {
+    // Andrew's monotone chain.
+    pub fn convex(points: &[[f32; 2]]) -> Self {
+        let mut sorted = points.to_vec();
+        sorted.sort_by(|a, b| a[0].total_cmp(&b[0]).then(a[1].total_cmp(&b[1])));
+        sorted.dedup();
+
+        let mut lower: Vec<[f32; 2]> = vec![];
+        for p in sorted.iter() {
+            while lower.len() >= 2 && cross(lower[lower.len() - 2], lower[lower.len() - 1], *p) <= 0.0 {
+                lower.pop();
+            }
+            lower.push(*p);
+        }
+        let mut upper: Vec<[f32; 2]> = vec![];
+        for p in sorted.iter().rev() {
+            while upper.len() >= 2 && cross(upper[upper.len() - 2], upper[upper.len() - 1], *p) <= 0.0 {
+                upper.pop();
+            }
+            upper.push(*p);
+        }
+        // each chain ends where the other starts
+        lower.pop();
+        upper.pop();
+        lower.extend(upper);
+
+        let radius = lower.iter().map(|p| p[0] * p[0] + p[1] * p[1]).fold(0.0, f32::max).sqrt();
+        Self { points: lower, radius }
+    }
+
+    // The hull rotated and moved to where `vehicle` is.
+    pub fn place(&self, vehicle: &Vehicle_100) -> Vec<[f32; 2]> {
+        let (s, c) = fmath::sin_cos(vehicle.vifo_theta.0);
+        self.points
+            .iter()
+            .map(|[x, y]| [vehicle.position_dx + x * c - y * s, vehicle.position_dy + x * s + y * c])
+            .collect()
+    }
+}
+
+// > 0 when a, b, c turn counter-clockwise
+fn cross(a: [f32; 2], b: [f32; 2], c: [f32; 2]) -> f32 {
+    (b[0] - a[0]) * (c[1] - a[1]) - (b[1] - a[1]) * (c[0] - a[0])
+}
+
+// Separating axis test on two placed hulls.
+pub fn polygons_overlap(a: &[[f32; 2]], b: &[[f32; 2]]) -> bool {
+    !has_separating_edge(a, b) && !has_separating_edge(b, a)
+}
+
+// Whether one of `a`'s edges has all of `b` on its outside.
+fn has_separating_edge(a: &[[f32; 2]], b: &[[f32; 2]]) -> bool {
+    (0..a.len()).any(|i| {
+        let (p, q) = (a[i], a[(i + 1) % a.len()]);
+        b.iter().all(|r| cross(p, q, *r) < 0.0)
+    })
+}
+
+// Whether a circle touches or lies inside a placed hull.
+pub fn polygon_touches_circle(polygon: &[[f32; 2]], center: [f32; 2], radius: f32) -> bool {
+    let inside = (0..polygon.len()).all(|i| cross(polygon[i], polygon[(i + 1) % polygon.len()], center) >= 0.0);
+    inside
+        || (0..polygon.len()).any(|i| {
+            segment_distance_squared(polygon[i], polygon[(i + 1) % polygon.len()], center) <= radius * radius
+        })
+}
+
+fn segment_distance_squared(a: [f32; 2], b: [f32; 2], p: [f32; 2]) -> f32 {
+    let (abx, aby) = (b[0] - a[0], b[1] - a[1]);
+    let (apx, apy) = (p[0] - a[0], p[1] - a[1]);
+    let length_squared = abx * abx + aby * aby;
+    let t = if length_squared > 0.0 { ((apx * abx + apy * aby) / length_squared).clamp(0.0, 1.0) } else { 0.0 };
+    let (dx, dy) = (apx - abx * t, apy - aby * t);
+    dx * dx + dy * dy
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cgmath::Rad;
+
+    fn at(x: f32, y: f32, theta: f32) -> Vehicle_100 {
+        Vehicle_100 { position_dx: x, position_dy: y, vifo_theta: Rad(theta), velocity_dx: 0.0, velocity_dy: 0.0 }
+    }
+
+    #[test]
+    fn hull_drops_inner_points() {
+        // a notched wedge and a point in its middle
+        let hull = Hull::convex(&[[18.0, 0.0], [-10.0, 10.0], [-5.0, 0.0], [-10.0, -10.0], [0.0, 0.0]]);
+        assert_eq!(hull.points, vec![[-10.0, -10.0], [18.0, 0.0], [-10.0, 10.0]]);
+        assert_eq!(hull.radius, 18.0);
+    }
+
+    #[test]
+    fn overlap_follows_the_shape_not_the_radius() {
+        let needle = Hull::convex(&[[0.02, 0.0], [-0.02, 0.002], [-0.02, -0.002]]);
+        // side by side and close, but the thin hulls don't touch
+        let a = needle.place(&at(0.0, 0.0, 0.0));
+        let b = needle.place(&at(0.0, 0.006, 0.0));
+        assert!(!polygons_overlap(&a, &b));
+        // turned across the first, the second one does
+        let c = needle.place(&at(0.0, 0.006, std::f32::consts::FRAC_PI_2));
+        assert!(polygons_overlap(&a, &c));
+    }
+
+    #[test]
+    fn circles_touch_edges_and_insides() {
+        let square = Hull::convex(&[[-1.0, -1.0], [1.0, -1.0], [1.0, 1.0], [-1.0, 1.0]]);
+        let placed = square.place(&at(0.0, 0.0, 0.0));
+        assert!(polygon_touches_circle(&placed, [0.0, 0.0], 0.1));
+        assert!(polygon_touches_circle(&placed, [1.05, 0.0], 0.1));
+        assert!(!polygon_touches_circle(&placed, [1.2, 0.0], 0.1));
+        // off a corner, closer along the axes than along the diagonal
+        assert!(!polygon_touches_circle(&placed, [1.08, 1.08], 0.1));
+    }
+}
@@ -0,0 +1,1223 @@
+// Headless, deterministic version of the game rules.
+//
+// The game_* components advanced the world by wall-clock deltas from key handlers and the
+// render loop, so no two runs were alike.  Here the world only moves in fixed ticks, and
+// the only thing fed in each tick is the controls both players pressed during it.  Given
+// the same seed, config and input stream, `update_game_state` produces bit-identical
+// states on any machine, which is what replays and the tests rely on.
+
+pub mod design;
+pub mod fmath;
+pub mod hull;
+pub mod pilot;
+pub mod rng;
+pub mod rules;
+pub mod vehicle;
+
+use std::fmt;
+use std::str::FromStr;
+
+use cgmath::Rad;
+use serde::{Deserialize, Serialize};
+
+pub use design::ShipDesign;
+pub use pilot::computer_controls;
+use rng::Rng;
+pub use rules::{presets, Collision, GameRules};
+pub use vehicle::Vehicle_100;
+
+pub const TICK_MILLIS: u32 = 16;
+const DELTA_SCALAR: f32 = (TICK_MILLIS as f32) * 0.001;
+
+pub const PLAYER_ONE: usize = 0;
+pub const PLAYER_TWO: usize = 1;
+
+// Bits of `Controls`.  A bit is set when the key went down (including auto-repeat)
+// at least once during the tick, matching the one-step-per-keydown feel of the handlers.
+pub const ROTATE_LEFT: u8 = 1;
+pub const ROTATE_RIGHT: u8 = 1 << 1;
+pub const THRUST: u8 = 1 << 2;
+pub const FIRE: u8 = 1 << 3;
+pub const HYPERSPACE: u8 = 1 << 4;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Controls(pub u8);
+
+impl Controls {
+    pub fn pressed(&self, bit: u8) -> bool {
+        self.0 & bit != 0
+    }
+
+    pub fn press(&mut self, bit: u8) {
+        self.0 |= bit;
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TickInput {
+    pub players: [Controls; 2],
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum GameMode {
+    LocalTwoPlayer,
+    // player two is flown by `computer_controls`
+    VersusComputer,
+}
+
+// As the mode appears in the address bar.
+impl fmt::Display for GameMode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            GameMode::LocalTwoPlayer => "local",
+            GameMode::VersusComputer => "computer",
+        })
+    }
+}
+
+impl FromStr for GameMode {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "local" => Ok(GameMode::LocalTwoPlayer),
+            "computer" => Ok(GameMode::VersusComputer),
+            _ => Err(()),
+        }
+    }
+}
+
+// Replays recorded before a field existed load with its default.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MatchConfig {
+    pub mode: GameMode,
+    // player one's, then player two's
+    pub ships: [ShipDesign; 2],
+    pub rules: GameRules,
+}
+
+impl Default for MatchConfig {
+    fn default() -> Self {
+        Self {
+            mode: GameMode::LocalTwoPlayer,
+            // as in the original
+            ships: [ShipDesign::Needle, ShipDesign::Wedge],
+            rules: GameRules::default(),
+        }
+    }
+}
+
+impl MatchConfig {
+    pub fn rounds_to_win(&self) -> u32 {
+        self.rules.scoring.best_of / 2 + 1
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RoundPhase {
+    // controls are ignored and nothing moves
+    Countdown,
+    Playing,
+    // someone is down or time ran out; the world plays on, but nobody flies or gets hurt
+    RoundOver,
+    // as after a single-round match: everything flies on, only the result is frozen
+    MatchOver,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MatchResult {
+    Undecided,
+    Winner(usize),
+    Draw,
+}
+
+// Things that happened during the last tick, for whoever draws or logs them.  The
+// velocity is the sum of the velocities of the two bodies that met.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SimEvent {
+    TorpedoHit {
+        player: usize,
+        position_dx: f32,
+        position_dy: f32,
+        velocity_dx: f32,
+        velocity_dy: f32,
+    },
+    VehicleCollision {
+        position_dx: f32,
+        position_dy: f32,
+        velocity_dx: f32,
+        velocity_dy: f32,
+    },
+    // The ships knocked apart, under the bounce rule, rather than destroying each other.
+    Bounce {
+        position_dx: f32,
+        position_dy: f32,
+        velocity_dx: f32,
+        velocity_dy: f32,
+    },
+    // A hit the ship survived, under damage rules; velocity is the ship's.
+    Damaged {
+        player: usize,
+        position_dx: f32,
+        position_dy: f32,
+        velocity_dx: f32,
+        velocity_dy: f32,
+    },
+    // A ship knocked to pieces by the other under the bounce rule.
+    Rammed {
+        player: usize,
+        position_dx: f32,
+        position_dy: f32,
+        velocity_dx: f32,
+        velocity_dy: f32,
+    },
+    // A ship vanished into hyperspace (`leaving`) or came back out of it.
+    Hyperspace {
+        player: usize,
+        position_dx: f32,
+        position_dy: f32,
+        leaving: bool,
+    },
+    // and didn't survive coming back; velocity is the ship's
+    HyperspaceBreakup {
+        player: usize,
+        position_dx: f32,
+        position_dy: f32,
+        velocity_dx: f32,
+        velocity_dy: f32,
+    },
+    // The engine fired; velocity is the ship's, after the impulse.
+    Thrust {
+        player: usize,
+        position_dx: f32,
+        position_dy: f32,
+        velocity_dx: f32,
+        velocity_dy: f32,
+        vifo_theta: f32,
+    },
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Player {
+    pub vehicle: Vehicle_100,
+    pub alive: bool,
+    // ticks of thrust left in the tank, and torpedoes left; None while there's no limit
+    pub fuel: Option<u32>,
+    pub torpedoes: Option<u32>,
+    // under damage rules; None when any hit destroys the ship
+    pub health: Option<Health>,
+    pub hyperspace: Hyperspace,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Health {
+    pub hull: f32,
+    pub shields: f32,
+    // ticks until the shields start coming back
+    pub recharge_in: u32,
+}
+
+// A ship's jumps this round.  While it's away it can't be seen, steered or hit.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Hyperspace {
+    // made so far
+    pub jumps: u32,
+    // ticks until it comes back
+    pub away: u32,
+    // ticks until it can jump again
+    pub cooldown: u32,
+}
+
+impl Player {
+    // alive and in the arena
+    pub fn present(&self) -> bool {
+        self.alive && self.hyperspace.away == 0
+    }
+
+    pub fn can_jump(&self, rules: &GameRules) -> bool {
+        self.present() && self.hyperspace.cooldown == 0 && self.hyperspace.jumps < rules.hyperspace.jumps
+    }
+
+    // An empty tank leaves the ship drifting until the next round.
+    pub fn can_thrust(&self) -> bool {
+        self.fuel != Some(0)
+    }
+
+    pub fn can_fire(&self) -> bool {
+        self.torpedoes != Some(0)
+    }
+
+    // Takes `amount` on the shields, and what they can't hold on the hull.  True if that
+    // was the end of the ship; without damage rules, any hit is.
+    fn take_damage(&mut self, amount: f32) -> bool {
+        let Some(health) = &mut self.health else { return true };
+        let absorbed = amount.min(health.shields);
+        health.shields -= absorbed;
+        health.hull -= amount - absorbed;
+        health.hull <= 0.0
+    }
+}
+
+// Takes one from a limited supply.
+fn draw_on(supply: &mut Option<u32>) {
+    if let Some(left) = supply {
+        *left = left.saturating_sub(1);
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Torpedo {
+    pub vehicle: Vehicle_100,
+    pub owner: usize,
+    pub age: u32,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct GameState {
+    pub seed: u64,
+    pub config: MatchConfig,
+    pub tick: u32,
+    pub players: [Player; 2],
+    pub torps_in_flight: Vec<Torpedo>,
+    // only hyperspace draws on it, for where a ship comes back and whether it survives
+    pub rng: Rng,
+    // whether the ships were touching after the last tick; a collision only does its
+    // damage as they meet
+    pub contact: bool,
+    // from 1
+    pub round: u32,
+    pub phase: RoundPhase,
+    // ticks spent in the current phase
+    pub phase_ticks: u32,
+    // ticks left on the round clock, which only runs while playing
+    pub clock: u32,
+    // rounds won
+    pub scores: [u32; 2],
+    pub result: MatchResult,
+    pub events: Vec<SimEvent>,
+}
+
+pub fn create_game_state(seed: u64, config: MatchConfig) -> GameState {
+    GameState {
+        seed,
+        tick: 0,
+        players: starting_players(&config.rules),
+        torps_in_flight: vec![],
+        rng: Rng::new(seed),
+        contact: false,
+        round: 1,
+        phase: RoundPhase::Countdown,
+        phase_ticks: 0,
+        clock: config.rules.round.round_ticks,
+        scores: [0, 0],
+        result: MatchResult::Undecided,
+        events: vec![],
+        config,
+    }
+}
+
+// In their corners, with full tanks and magazines.
+fn starting_players(rules: &GameRules) -> [Player; 2] {
+    let player_one = Player {
+        vehicle: Vehicle_100 {
+            position_dx: 0.3,
+            position_dy: 0.3,
+            vifo_theta: Rad(0.3),
+            velocity_dx: 0.0,
+            velocity_dy: 0.0,
+        },
+        alive: true,
+        fuel: rules.ship.fuel,
+        torpedoes: rules.torpedo.magazine,
+        health: rules.damage.map(|d| Health { hull: d.hull, shields: d.shields, recharge_in: 0 }),
+        hyperspace: Hyperspace::default(),
+    };
+    let player_two = Player {
+        vehicle: Vehicle_100 {
+            position_dx: -0.4,
+            position_dy: -0.4,
+            vifo_theta: Rad(-0.3),
+            velocity_dx: 0.0,
+            velocity_dy: 0.0,
+        },
+        alive: true,
+        fuel: rules.ship.fuel,
+        torpedoes: rules.torpedo.magazine,
+        health: rules.damage.map(|d| Health { hull: d.hull, shields: d.shields, recharge_in: 0 }),
+        hyperspace: Hyperspace::default(),
+    };
+    [player_one, player_two]
+}
+
+// Advance the world, and the round, by exactly one tick.  The world keeps moving after
+// the match is decided (surviving ship, torpedoes in flight); only the result is frozen.
+pub fn update_game_state(game_state: &mut GameState, input: &TickInput) {
+    game_state.events.clear();
+
+    let phase = game_state.phase;
+    let mut lost = [false, false];
+    if phase != RoundPhase::Countdown {
+        let flying = phase != RoundPhase::RoundOver;
+        lost = advance_world(game_state, input, flying);
+    }
+    let hit = match phase {
+        RoundPhase::Playing | RoundPhase::MatchOver => detect_collisions(game_state),
+        _ => [false, false],
+    };
+    advance_round(game_state, [lost[0] || hit[0], lost[1] || hit[1]]);
+
+    game_state.tick += 1;
+}
+
+// Moves everything on by a tick, after applying the controls if the ships are `flying`.
+// Says which players were lost in hyperspace.  A ship in hyperspace when the round is
+// decided stays there until the next, so it can't break up after the round is scored.
+fn advance_world(game_state: &mut GameState, input: &TickInput, flying: bool) -> [bool; 2] {
+    let rules = &game_state.config.rules;
+    let mut lost = [false, false];
+    for (idx, player) in game_state.players.iter_mut().enumerate() {
+        if !player.alive {
+            continue;
+        }
+        if player.hyperspace.away > 0 {
+            if !flying {
+                continue;
+            }
+            player.hyperspace.away -= 1;
+            if player.hyperspace.away == 0 {
+                lost[idx] = come_back(player, idx, rules, &mut game_state.rng, &mut game_state.events);
+            }
+            continue;
+        }
+        player.hyperspace.cooldown = player.hyperspace.cooldown.saturating_sub(1);
+        if let (Some(health), Some(damage)) = (&mut player.health, &rules.damage) {
+            if health.recharge_in > 0 {
+                health.recharge_in -= 1;
+            } else {
+                health.shields = (health.shields + damage.shield_recharge * DELTA_SCALAR).min(damage.shields);
+            }
+        }
+        if !flying {
+            continue;
+        }
+        let controls = input.players[idx];
+
+        if controls.pressed(HYPERSPACE) && player.can_jump(rules) {
+            player.hyperspace.jumps += 1;
+            player.hyperspace.away = rules.hyperspace.duration_ticks;
+            game_state.events.push(SimEvent::Hyperspace {
+                player: idx,
+                position_dx: player.vehicle.position_dx,
+                position_dy: player.vehicle.position_dy,
+                leaving: true,
+            });
+            continue;
+        }
+
+        if controls.pressed(ROTATE_LEFT) {
+            player.vehicle.vifo_theta += Rad(rules.ship.rotation_step);
+        }
+        if controls.pressed(ROTATE_RIGHT) {
+            player.vehicle.vifo_theta -= Rad(rules.ship.rotation_step);
+        }
+        if controls.pressed(THRUST) && player.can_thrust() {
+            draw_on(&mut player.fuel);
+            let vehicle = &mut player.vehicle;
+            let (hx, hy) = vehicle.heading();
+            vehicle.velocity_dx += hx * rules.ship.thrust;
+            vehicle.velocity_dy += hy * rules.ship.thrust;
+            game_state.events.push(SimEvent::Thrust {
+                player: idx,
+                position_dx: vehicle.position_dx,
+                position_dy: vehicle.position_dy,
+                velocity_dx: vehicle.velocity_dx,
+                velocity_dy: vehicle.velocity_dy,
+                vifo_theta: vehicle.vifo_theta.0,
+            });
+        }
+        if controls.pressed(FIRE) && player.can_fire() {
+            draw_on(&mut player.torpedoes);
+            if let (Some(health), Some(damage)) = (&mut player.health, &rules.damage) {
+                health.recharge_in = damage.recharge_delay_ticks;
+            }
+            let vehicle = &player.vehicle;
+            let (hx, hy) = vehicle.heading();
+            game_state.torps_in_flight.push(Torpedo {
+                vehicle: Vehicle_100 {
+                    position_dx: vehicle.position_dx,
+                    position_dy: vehicle.position_dy,
+                    vifo_theta: vehicle.vifo_theta,
+                    velocity_dx: vehicle.velocity_dx + hx * rules.torpedo.speed,
+                    velocity_dy: vehicle.velocity_dy + hy * rules.torpedo.speed,
+                },
+                owner: idx,
+                age: 0,
+            });
+        }
+    }
+
+    let gravity = rules.gravity;
+    for player in game_state.players.iter_mut().filter(|p| p.present()) {
+        pull(&mut player.vehicle, &gravity);
+        player.vehicle.advance(DELTA_SCALAR);
+        player.vehicle.wrap();
+    }
+
+    // Torpedoes don't wrap; they leave the arena and are gone.
+    for torp in game_state.torps_in_flight.iter_mut() {
+        pull(&mut torp.vehicle, &gravity);
+        torp.vehicle.advance(DELTA_SCALAR);
+        torp.age += 1;
+    }
+    game_state.torps_in_flight.retain(|torp| !torp.vehicle.out_of_bounds());
+    lost
+}
+
+// Brings a ship back from hyperspace somewhere at random, heading and velocity as they
+// were, or breaks it up on the way.  True if it broke up.
+fn come_back(player: &mut Player, idx: usize, rules: &GameRules, rng: &mut Rng, events: &mut Vec<SimEvent>) -> bool {
+    let vehicle = &mut player.vehicle;
+    vehicle.position_dx = rng.range(-1.0, 1.0);
+    vehicle.position_dy = rng.range(-1.0, 1.0);
+    player.hyperspace.cooldown = rules.hyperspace.cooldown_ticks;
+    if rng.next_f32() < rules.hyperspace.risk_of(player.hyperspace.jumps) {
+        player.alive = false;
+        events.push(SimEvent::HyperspaceBreakup {
+            player: idx,
+            position_dx: vehicle.position_dx,
+            position_dy: vehicle.position_dy,
+            velocity_dx: vehicle.velocity_dx,
+            velocity_dy: vehicle.velocity_dy,
+        });
+        return true;
+    }
+    events.push(SimEvent::Hyperspace {
+        player: idx,
+        position_dx: vehicle.position_dx,
+        position_dy: vehicle.position_dy,
+        leaving: false,
+    });
+    false
+}
+
+// A tick of the star's pull, which falls off with the square of the distance to the
+// centre.  Without a star nothing is touched, so classic games stay bit-for-bit as they
+// were before there could be one.
+fn pull(vehicle: &mut Vehicle_100, gravity: &rules::GravityRules) {
+    if gravity.strength == 0.0 {
+        return;
+    }
+    let (dx, dy) = (vehicle.position_dx, vehicle.position_dy);
+    let distance_squared = (dx * dx + dy * dy).max(gravity.softening * gravity.softening);
+    let distance = distance_squared.sqrt();
+    let impulse = gravity.strength / distance_squared * DELTA_SCALAR;
+    vehicle.velocity_dx -= dx / distance * impulse;
+    vehicle.velocity_dy -= dy / distance * impulse;
+}
+
+// Damages or destroys whatever was hit, and says which players were destroyed.
+fn detect_collisions(game_state: &mut GameState) -> [bool; 2] {
+    let hulls = game_state.config.ships.map(|design| design.hull());
+    let torpedo = game_state.config.rules.torpedo;
+    let damage = game_state.config.rules.damage;
+    let collision = game_state.config.rules.collision;
+    let placed = [
+        hulls[PLAYER_ONE].place(&game_state.players[PLAYER_ONE].vehicle),
+        hulls[PLAYER_TWO].place(&game_state.players[PLAYER_TWO].vehicle),
+    ];
+    let mut destroyed = [false; 2];
+    let mut spent: Vec<usize> = vec![];
+
+    // A ship destroyed this tick is out of the way of any more torpedoes.
+    for (t_idx, torp) in game_state.torps_in_flight.iter().enumerate() {
+        for (p_idx, player) in game_state.players.iter_mut().enumerate() {
+            if destroyed[p_idx] || !player.present() || (torp.owner == p_idx && torp.age < torpedo.arming_ticks) {
+                continue;
+            }
+            let reach = hulls[p_idx].radius + torpedo.radius;
+            let center = [torp.vehicle.position_dx, torp.vehicle.position_dy];
+            if torp.vehicle.distance_squared(&player.vehicle) < reach * reach
+                && hull::polygon_touches_circle(&placed[p_idx], center, torpedo.radius)
+            {
+                spent.push(t_idx);
+                let harm = damage.map_or(0.0, |d| d.torpedo_damage * torp.vehicle.speed_relative_to(&player.vehicle));
+                if player.take_damage(harm) {
+                    destroyed[p_idx] = true;
+                    game_state.events.push(SimEvent::TorpedoHit {
+                        player: p_idx,
+                        position_dx: player.vehicle.position_dx,
+                        position_dy: player.vehicle.position_dy,
+                        velocity_dx: player.vehicle.velocity_dx + torp.vehicle.velocity_dx,
+                        velocity_dy: player.vehicle.velocity_dy + torp.vehicle.velocity_dy,
+                    });
+                } else {
+                    game_state.events.push(damaged(p_idx, player));
+                }
+                break;
+            }
+        }
+    }
+
+    let [one, two] = &mut game_state.players;
+    let reach = hulls[PLAYER_ONE].radius + hulls[PLAYER_TWO].radius;
+    let touching = one.present()
+        && two.present()
+        && one.vehicle.distance_squared(&two.vehicle) < reach * reach
+        && hull::polygons_overlap(&placed[PLAYER_ONE], &placed[PLAYER_TWO]);
+    let (position_dx, position_dy) = (
+        (one.vehicle.position_dx + two.vehicle.position_dx) / 2.0,
+        (one.vehicle.position_dy + two.vehicle.position_dy) / 2.0,
+    );
+    match collision {
+        Collision::Destroy if touching => {
+            destroyed = [true, true];
+            game_state.events.push(SimEvent::VehicleCollision {
+                position_dx,
+                position_dy,
+                velocity_dx: one.vehicle.velocity_dx + two.vehicle.velocity_dx,
+                velocity_dy: one.vehicle.velocity_dy + two.vehicle.velocity_dy,
+            });
+        }
+        Collision::Bounce if touching => {
+            // the knock is judged on how fast they met, before the bounce
+            if let Some(damage) = damage.filter(|_| !game_state.contact) {
+                let harm = damage.collision_damage * one.vehicle.speed_relative_to(&two.vehicle);
+                for (idx, player) in [(PLAYER_ONE, &mut *one), (PLAYER_TWO, &mut *two)] {
+                    if destroyed[idx] {
+                        continue;
+                    }
+                    if player.take_damage(harm) {
+                        destroyed[idx] = true;
+                        game_state.events.push(SimEvent::Rammed {
+                            player: idx,
+                            position_dx: player.vehicle.position_dx,
+                            position_dy: player.vehicle.position_dy,
+                            velocity_dx: player.vehicle.velocity_dx,
+                            velocity_dy: player.vehicle.velocity_dy,
+                        });
+                    } else {
+                        game_state.events.push(damaged(idx, player));
+                    }
+                }
+            }
+            // a wreck knocks nothing about, and is knocked about by nothing
+            if destroyed == [false, false] && one.vehicle.bounce_off(&mut two.vehicle) {
+                game_state.events.push(SimEvent::Bounce {
+                    position_dx,
+                    position_dy,
+                    velocity_dx: one.vehicle.velocity_dx + two.vehicle.velocity_dx,
+                    velocity_dy: one.vehicle.velocity_dy + two.vehicle.velocity_dy,
+                });
+            }
+        }
+        _ => {}
+    }
+    game_state.contact = touching;
+
+    for idx in spent.into_iter().rev() {
+        game_state.torps_in_flight.remove(idx);
+    }
+
+    for (idx, player) in game_state.players.iter_mut().enumerate() {
+        if destroyed[idx] {
+            player.alive = false;
+        }
+    }
+    destroyed
+}
+
+fn damaged(idx: usize, player: &Player) -> SimEvent {
+    SimEvent::Damaged {
+        player: idx,
+        position_dx: player.vehicle.position_dx,
+        position_dy: player.vehicle.position_dy,
+        velocity_dx: player.vehicle.velocity_dx,
+        velocity_dy: player.vehicle.velocity_dy,
+    }
+}
+
+// Runs the round clock and moves between phases.  A round goes to the only ship left
+// standing.  If time runs out nobody scores it, and if both go down together it's
+// nobody's or, under shared rounds, both players'.
+fn advance_round(game_state: &mut GameState, destroyed: [bool; 2]) {
+    let rules = &game_state.config.rules;
+    let (round, shared_rounds) = (rules.round, rules.scoring.shared_rounds);
+    game_state.phase_ticks += 1;
+    match game_state.phase {
+        RoundPhase::Countdown if game_state.phase_ticks >= round.countdown_ticks => {
+            enter_phase(game_state, RoundPhase::Playing);
+        }
+        RoundPhase::Playing => {
+            game_state.clock = game_state.clock.saturating_sub(1);
+            if destroyed == [false, false] && game_state.clock > 0 {
+                return;
+            }
+            match destroyed {
+                [true, false] => game_state.scores[PLAYER_TWO] += 1,
+                [false, true] => game_state.scores[PLAYER_ONE] += 1,
+                [true, true] if shared_rounds => game_state.scores.iter_mut().for_each(|score| *score += 1),
+                _ => (),
+            }
+            game_state.result = match_result(game_state);
+            if game_state.result == MatchResult::Undecided {
+                enter_phase(game_state, RoundPhase::RoundOver);
+            } else {
+                enter_phase(game_state, RoundPhase::MatchOver);
+            }
+        }
+        RoundPhase::RoundOver if game_state.phase_ticks >= round.round_over_ticks => {
+            game_state.players = starting_players(&game_state.config.rules);
+            game_state.torps_in_flight.clear();
+            game_state.round += 1;
+            game_state.clock = round.round_ticks;
+            enter_phase(game_state, RoundPhase::Countdown);
+        }
+        _ => (),
+    }
+}
+
+fn enter_phase(game_state: &mut GameState, phase: RoundPhase) {
+    game_state.phase = phase;
+    game_state.phase_ticks = 0;
+}
+
+// Decided once someone has won enough rounds, or after the last round on points.
+fn match_result(game_state: &GameState) -> MatchResult {
+    let [one, two] = game_state.scores;
+    let to_win = game_state.config.rounds_to_win();
+    if one >= to_win || two >= to_win || game_state.round >= game_state.config.rules.scoring.best_of {
+        match one.cmp(&two) {
+            std::cmp::Ordering::Greater => MatchResult::Winner(PLAYER_ONE),
+            std::cmp::Ordering::Less => MatchResult::Winner(PLAYER_TWO),
+            std::cmp::Ordering::Equal => MatchResult::Draw,
+        }
+    } else {
+        MatchResult::Undecided
+    }
+}
+
+// FNV-1a over every field that affects the future of the match.
+pub fn state_hash(game_state: &GameState) -> u64 {
+    let mut hash = Fnv::new();
+
+    hash.write(&game_state.seed.to_le_bytes());
+    hash.write(&game_state.tick.to_le_bytes());
+    for player in game_state.players.iter() {
+        hash.write_vehicle(&player.vehicle);
+        hash.write(&[player.alive as u8]);
+        // Unlimited supplies add nothing, so hashes from before there were limits still
+        // hold.
+        for supply in [player.fuel, player.torpedoes].into_iter().flatten() {
+            hash.write(&supply.to_le_bytes());
+        }
+        if let Some(health) = player.health {
+            for f in [health.hull, health.shields] {
+                hash.write(&f.to_bits().to_le_bytes());
+            }
+            hash.write(&health.recharge_in.to_le_bytes());
+        }
+        // and nor does hyperspace until someone jumps
+        let jumps = player.hyperspace;
+        if jumps != Hyperspace::default() {
+            for n in [jumps.jumps, jumps.away, jumps.cooldown] {
+                hash.write(&n.to_le_bytes());
+            }
+        }
+    }
+    if game_state.contact {
+        hash.write(&[1]);
+    }
+    // The seed decides where the generator starts, so it only adds anything once drawn on.
+    if game_state.rng != Rng::new(game_state.seed) {
+        hash.write(&game_state.rng.state().to_le_bytes());
+    }
+    hash.write(&(game_state.torps_in_flight.len() as u32).to_le_bytes());
+    for torp in game_state.torps_in_flight.iter() {
+        hash.write_vehicle(&torp.vehicle);
+        hash.write(&[torp.owner as u8]);
+        hash.write(&torp.age.to_le_bytes());
+    }
+    let phase = match game_state.phase {
+        RoundPhase::Countdown => 0u8,
+        RoundPhase::Playing => 1,
+        RoundPhase::RoundOver => 2,
+        RoundPhase::MatchOver => 3,
+    };
+    hash.write(&game_state.round.to_le_bytes());
+    hash.write(&[phase]);
+    hash.write(&game_state.phase_ticks.to_le_bytes());
+    hash.write(&game_state.clock.to_le_bytes());
+    for score in game_state.scores {
+        hash.write(&score.to_le_bytes());
+    }
+    let result = match game_state.result {
+        MatchResult::Undecided => 0u8,
+        MatchResult::Winner(idx) => 1 + idx as u8,
+        MatchResult::Draw => 3,
+    };
+    hash.write(&[result]);
+
+    hash.0
+}
+
+struct Fnv(u64);
+
+impl Fnv {
+    fn new() -> Self {
+        Self(0xcbf2_9ce4_8422_2325)
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for b in bytes {
+            self.0 ^= *b as u64;
+            self.0 = self.0.wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+
+    fn write_vehicle(&mut self, v: &Vehicle_100) {
+        for f in [v.position_dx, v.position_dy, v.vifo_theta.0, v.velocity_dx, v.velocity_dy] {
+            self.write(&f.to_bits().to_le_bytes());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const COUNTDOWN_TICKS: u32 = 120;
+    const ROUND_OVER_TICKS: u32 = 150;
+
+    fn run(game_state: &mut GameState, ticks: u32) {
+        for _ in 0..ticks {
+            update_game_state(game_state, &TickInput::default());
+        }
+    }
+
+    #[test]
+    fn countdown_holds_the_ships() {
+        let mut game_state = create_game_state(3, MatchConfig::default());
+        let mut thrust = TickInput::default();
+        thrust.players[PLAYER_ONE].press(THRUST);
+        update_game_state(&mut game_state, &thrust);
+        assert_eq!(game_state.players, starting_players(&game_state.config.rules));
+        run(&mut game_state, COUNTDOWN_TICKS - 1);
+        assert_eq!(game_state.phase, RoundPhase::Playing);
+    }
+
+    #[test]
+    fn a_kill_scores_the_round_and_the_next_begins() {
+        let mut game_state = create_game_state(3, MatchConfig::default());
+        run(&mut game_state, COUNTDOWN_TICKS);
+        game_state.torps_in_flight.push(Torpedo {
+            vehicle: game_state.players[PLAYER_TWO].vehicle,
+            owner: PLAYER_ONE,
+            age: 0,
+        });
+        run(&mut game_state, 1);
+        assert_eq!(game_state.scores, [1, 0]);
+        assert_eq!(game_state.phase, RoundPhase::RoundOver);
+        assert_eq!(game_state.result, MatchResult::Undecided);
+
+        run(&mut game_state, ROUND_OVER_TICKS);
+        assert_eq!(game_state.round, 2);
+        assert_eq!(game_state.phase, RoundPhase::Countdown);
+        assert_eq!(game_state.players, starting_players(&game_state.config.rules));
+        assert!(game_state.torps_in_flight.is_empty());
+        assert_eq!(game_state.clock, game_state.config.rules.round.round_ticks);
+    }
+
+    #[test]
+    fn running_out_of_time_scores_nobody() {
+        let mut config = MatchConfig::default();
+        config.rules.scoring.best_of = 1;
+        config.rules.round.round_ticks = 50;
+        let mut game_state = create_game_state(3, config);
+        run(&mut game_state, COUNTDOWN_TICKS + 49);
+        assert_eq!(game_state.phase, RoundPhase::Playing);
+        assert_eq!(game_state.clock, 1);
+        run(&mut game_state, 1);
+        assert_eq!(game_state.scores, [0, 0]);
+        assert_eq!(game_state.phase, RoundPhase::MatchOver);
+        assert_eq!(game_state.result, MatchResult::Draw);
+    }
+
+    #[test]
+    fn the_match_goes_to_whoever_wins_enough_rounds() {
+        let mut game_state = create_game_state(3, MatchConfig::default());
+        game_state.scores = [1, 2];
+        game_state.round = 4;
+        run(&mut game_state, COUNTDOWN_TICKS);
+        game_state.torps_in_flight.push(Torpedo {
+            vehicle: game_state.players[PLAYER_ONE].vehicle,
+            owner: PLAYER_TWO,
+            age: 0,
+        });
+        run(&mut game_state, 1);
+        assert_eq!(game_state.scores, [1, 3]);
+        assert_eq!(game_state.phase, RoundPhase::MatchOver);
+        assert_eq!(game_state.result, MatchResult::Winner(PLAYER_TWO));
+    }
+
+    #[test]
+    fn under_shared_rounds_going_down_together_scores_both() {
+        let mut config = MatchConfig::default();
+        config.rules.scoring.shared_rounds = true;
+        let mut game_state = create_game_state(3, config);
+        run(&mut game_state, COUNTDOWN_TICKS);
+        for (victim, owner) in [(PLAYER_ONE, PLAYER_TWO), (PLAYER_TWO, PLAYER_ONE)] {
+            game_state.torps_in_flight.push(Torpedo { vehicle: game_state.players[victim].vehicle, owner, age: 0 });
+        }
+        run(&mut game_state, 1);
+        assert_eq!(game_state.scores, [1, 1]);
+        assert_eq!(game_state.phase, RoundPhase::RoundOver);
+    }
+
+    #[test]
+    fn the_star_pulls_towards_the_centre() {
+        let mut config = MatchConfig::default();
+        config.rules.gravity.strength = 0.05;
+        let mut game_state = create_game_state(3, config);
+        run(&mut game_state, COUNTDOWN_TICKS + 10);
+        // player one starts up and to the right of the centre, player two down and left
+        let [one, two] = [&game_state.players[PLAYER_ONE].vehicle, &game_state.players[PLAYER_TWO].vehicle];
+        assert!(one.velocity_dx < 0.0 && one.velocity_dy < 0.0);
+        assert!(two.velocity_dx > 0.0 && two.velocity_dy > 0.0);
+
+        // and without one, nothing drifts
+        let mut game_state = create_game_state(3, MatchConfig::default());
+        run(&mut game_state, COUNTDOWN_TICKS + 10);
+        assert_eq!(game_state.players, starting_players(&game_state.config.rules));
+    }
+
+    #[test]
+    fn fuel_and_torpedoes_run_out_and_refill_for_the_next_round() {
+        let mut config = MatchConfig::default();
+        config.rules.ship.fuel = Some(3);
+        config.rules.torpedo.magazine = Some(2);
+        let mut game_state = create_game_state(3, config);
+        run(&mut game_state, COUNTDOWN_TICKS);
+        let mut both = TickInput::default();
+        both.players[PLAYER_ONE].press(THRUST);
+        both.players[PLAYER_ONE].press(FIRE);
+        for _ in 0..5 {
+            update_game_state(&mut game_state, &both);
+        }
+        let player = &game_state.players[PLAYER_ONE];
+        assert_eq!((player.fuel, player.torpedoes), (Some(0), Some(0)));
+        assert!(!player.can_thrust() && !player.can_fire());
+        assert_eq!(game_state.torps_in_flight.len(), 2);
+        let velocity = (player.vehicle.velocity_dx, player.vehicle.velocity_dy);
+        update_game_state(&mut game_state, &both);
+        let player = &game_state.players[PLAYER_ONE];
+        assert_eq!((player.vehicle.velocity_dx, player.vehicle.velocity_dy), velocity);
+        assert!(game_state.events.is_empty());
+
+        // the other ship is untouched, and a new round fills both up again
+        assert_eq!(game_state.players[PLAYER_TWO].fuel, Some(3));
+        game_state.players[PLAYER_TWO].alive = false;
+        game_state.phase = RoundPhase::RoundOver;
+        game_state.phase_ticks = 0;
+        run(&mut game_state, ROUND_OVER_TICKS);
+        assert_eq!(game_state.round, 2);
+        let player = &game_state.players[PLAYER_ONE];
+        assert_eq!((player.fuel, player.torpedoes), (Some(3), Some(2)));
+    }
+
+    fn jump(game_state: &mut GameState, idx: usize) {
+        let mut input = TickInput::default();
+        input.players[idx].press(HYPERSPACE);
+        update_game_state(game_state, &input);
+    }
+
+    #[test]
+    fn hyperspace_hides_the_ship_then_brings_it_back_somewhere_else() {
+        let mut config = MatchConfig::default();
+        config.rules.hyperspace.risk = 0.0;
+        config.rules.hyperspace.risk_per_jump = 0.0;
+        let hyperspace = config.rules.hyperspace;
+        let mut game_state = create_game_state(3, config);
+        run(&mut game_state, COUNTDOWN_TICKS);
+        let before = game_state.players[PLAYER_ONE].vehicle;
+        jump(&mut game_state, PLAYER_ONE);
+        assert!(!game_state.players[PLAYER_ONE].present());
+        assert!(matches!(game_state.events[..], [SimEvent::Hyperspace { player: PLAYER_ONE, leaving: true, .. }]));
+
+        // torpedoes pass through where it was
+        game_state.torps_in_flight.push(Torpedo { vehicle: before, owner: PLAYER_TWO, age: 0 });
+        run(&mut game_state, hyperspace.duration_ticks - 1);
+        assert!(!game_state.players[PLAYER_ONE].present());
+        run(&mut game_state, 1);
+        assert!(game_state.players[PLAYER_ONE].alive);
+        assert_eq!(game_state.phase, RoundPhase::Playing);
+        assert!(matches!(game_state.events[..], [SimEvent::Hyperspace { player: PLAYER_ONE, leaving: false, .. }]));
+        let after = game_state.players[PLAYER_ONE].vehicle;
+        assert!(game_state.players[PLAYER_ONE].present());
+        assert_ne!((after.position_dx, after.position_dy), (before.position_dx, before.position_dy));
+        assert_eq!(after.vifo_theta, before.vifo_theta);
+
+        // cooling down, and then out of jumps
+        jump(&mut game_state, PLAYER_ONE);
+        assert!(game_state.players[PLAYER_ONE].present());
+        game_state.players[PLAYER_ONE].hyperspace.cooldown = 0;
+        game_state.players[PLAYER_ONE].hyperspace.jumps = hyperspace.jumps;
+        jump(&mut game_state, PLAYER_ONE);
+        assert!(game_state.players[PLAYER_ONE].present());
+    }
+
+    #[test]
+    fn where_a_ship_comes_back_follows_the_seed() {
+        let come_back_at = |seed: u64| {
+            let mut game_state = create_game_state(seed, MatchConfig::default());
+            run(&mut game_state, COUNTDOWN_TICKS);
+            jump(&mut game_state, PLAYER_TWO);
+            let duration = game_state.config.rules.hyperspace.duration_ticks;
+            run(&mut game_state, duration);
+            (game_state.players[PLAYER_TWO].vehicle, state_hash(&game_state))
+        };
+        assert_eq!(come_back_at(11), come_back_at(11));
+        assert_ne!(come_back_at(11).0, come_back_at(12).0);
+    }
+
+    #[test]
+    fn a_ship_that_breaks_up_in_hyperspace_loses_the_round() {
+        let mut config = MatchConfig::default();
+        config.rules.hyperspace.risk = 1.0;
+        let duration = config.rules.hyperspace.duration_ticks;
+        let mut game_state = create_game_state(3, config);
+        run(&mut game_state, COUNTDOWN_TICKS);
+        jump(&mut game_state, PLAYER_ONE);
+        run(&mut game_state, duration);
+        assert!(!game_state.players[PLAYER_ONE].alive);
+        assert!(matches!(game_state.events[..], [SimEvent::HyperspaceBreakup { player: PLAYER_ONE, .. }]));
+        assert_eq!(game_state.scores, [0, 1]);
+        assert_eq!(game_state.phase, RoundPhase::RoundOver);
+    }
+
+    #[test]
+    fn a_round_decided_while_a_ship_is_away_stays_decided() {
+        let mut config = MatchConfig::default();
+        config.rules.hyperspace.risk = 1.0;
+        let duration = config.rules.hyperspace.duration_ticks;
+        let mut game_state = create_game_state(3, config);
+        run(&mut game_state, COUNTDOWN_TICKS);
+        jump(&mut game_state, PLAYER_TWO);
+        let vehicle = game_state.players[PLAYER_ONE].vehicle;
+        game_state.torps_in_flight.push(Torpedo { vehicle, owner: PLAYER_TWO, age: 0 });
+        run(&mut game_state, 1);
+        assert_eq!(game_state.scores, [0, 1]);
+        assert_eq!(game_state.phase, RoundPhase::RoundOver);
+
+        // it would never have come back, but the round is over before it tries
+        run(&mut game_state, duration);
+        assert!(game_state.players[PLAYER_TWO].alive);
+        assert!(!game_state.players[PLAYER_TWO].present());
+        assert!(game_state.events.is_empty());
+        run(&mut game_state, ROUND_OVER_TICKS - duration);
+        assert_eq!(game_state.round, 2);
+        assert!(game_state.players[PLAYER_TWO].present());
+        assert_eq!(game_state.scores, [0, 1]);
+    }
+
+    #[test]
+    fn every_jump_is_riskier_than_the_last() {
+        let hyperspace = rules::HyperspaceRules::default();
+        let risks: Vec<f32> = (1..=6).map(|jump| hyperspace.risk_of(jump)).collect();
+        assert_eq!(risks[0], hyperspace.risk);
+        assert!(risks.windows(2).all(|pair| pair[0] < pair[1] || pair[1] == 1.0));
+        assert_eq!(risks[5], 1.0);
+    }
+
+    fn with_shields() -> (GameState, rules::DamageRules) {
+        let damage = rules::DamageRules::default();
+        let mut config = MatchConfig::default();
+        config.rules.damage = Some(damage);
+        config.rules.collision = Collision::Bounce;
+        let mut game_state = create_game_state(3, config);
+        run(&mut game_state, COUNTDOWN_TICKS);
+        (game_state, damage)
+    }
+
+    // a torpedo of player one's on top of player two, meeting it at `speed`
+    fn torpedo_into_player_two(game_state: &mut GameState, speed: f32) {
+        let mut vehicle = game_state.players[PLAYER_TWO].vehicle;
+        vehicle.velocity_dx += speed;
+        game_state.torps_in_flight.push(Torpedo { vehicle, owner: PLAYER_ONE, age: 0 });
+        run(game_state, 1);
+    }
+
+    #[test]
+    fn shields_take_a_hit_before_the_hull_and_the_hull_before_the_ship() {
+        let (mut game_state, damage) = with_shields();
+        torpedo_into_player_two(&mut game_state, 0.3);
+        let health = game_state.players[PLAYER_TWO].health.unwrap();
+        assert!((health.shields - (damage.shields - damage.torpedo_damage * 0.3)).abs() < 1e-3);
+        assert_eq!(health.hull, damage.hull);
+        assert!(game_state.events.iter().any(|e| matches!(e, SimEvent::Damaged { player: PLAYER_TWO, .. })));
+        assert!(game_state.torps_in_flight.is_empty());
+
+        torpedo_into_player_two(&mut game_state, 0.3);
+        let health = game_state.players[PLAYER_TWO].health.unwrap();
+        assert_eq!(health.shields, 0.0);
+        assert!(health.hull < damage.hull);
+        assert_eq!(game_state.phase, RoundPhase::Playing);
+
+        while game_state.players[PLAYER_TWO].alive {
+            torpedo_into_player_two(&mut game_state, 0.3);
+        }
+        assert!(game_state.players[PLAYER_TWO].health.unwrap().hull <= 0.0);
+        assert_eq!(game_state.scores, [1, 0]);
+    }
+
+    #[test]
+    fn a_ship_is_only_destroyed_once_however_many_torpedoes_hit_it() {
+        let mut game_state = create_game_state(3, MatchConfig::default());
+        run(&mut game_state, COUNTDOWN_TICKS);
+        let vehicle = game_state.players[PLAYER_TWO].vehicle;
+        for _ in 0..2 {
+            game_state.torps_in_flight.push(Torpedo { vehicle, owner: PLAYER_ONE, age: 0 });
+        }
+        run(&mut game_state, 1);
+        let hits = game_state.events.iter().filter(|e| matches!(e, SimEvent::TorpedoHit { .. })).count();
+        assert_eq!(hits, 1);
+        assert_eq!(game_state.torps_in_flight.len(), 1);
+        assert_eq!(game_state.scores, [1, 0]);
+    }
+
+    #[test]
+    fn faster_hits_do_more_damage() {
+        let shields_after = |speed: f32| {
+            let (mut game_state, _) = with_shields();
+            torpedo_into_player_two(&mut game_state, speed);
+            game_state.players[PLAYER_TWO].health.unwrap().shields
+        };
+        assert!(shields_after(0.4) < shields_after(0.2));
+        assert!(shields_after(0.2) < shields_after(0.1));
+    }
+
+    #[test]
+    fn shields_recharge_only_while_holding_fire() {
+        let (mut game_state, damage) = with_shields();
+        let mut fire = TickInput::default();
+        fire.players[PLAYER_ONE].press(FIRE);
+        update_game_state(&mut game_state, &fire);
+        game_state.players[PLAYER_ONE].health.as_mut().unwrap().shields = 0.0;
+        run(&mut game_state, damage.recharge_delay_ticks);
+        assert_eq!(game_state.players[PLAYER_ONE].health.unwrap().shields, 0.0);
+
+        // a second's recharge
+        run(&mut game_state, 1000 / TICK_MILLIS);
+        let shields = game_state.players[PLAYER_ONE].health.unwrap().shields;
+        assert!((shields - damage.shield_recharge * (1000 / TICK_MILLIS) as f32 * DELTA_SCALAR).abs() < 1e-3);
+        run(&mut game_state, 3750);
+        assert_eq!(game_state.players[PLAYER_ONE].health.unwrap().shields, damage.shields);
+    }
+
+    #[test]
+    fn ramming_hurts_both_ships_once_per_meeting() {
+        let (mut game_state, damage) = with_shields();
+        let [one, two] = &mut game_state.players;
+        two.vehicle.position_dx = one.vehicle.position_dx + 0.01;
+        two.vehicle.position_dy = one.vehicle.position_dy;
+        one.vehicle.velocity_dx = 0.1;
+        two.vehicle.velocity_dx = -0.1;
+        run(&mut game_state, 1);
+        let expected = damage.shields - damage.collision_damage * 0.2;
+        for player in game_state.players.iter() {
+            assert!((player.health.unwrap().shields - expected).abs() < 1e-3);
+        }
+        // head on, so they swap velocities
+        assert_eq!(game_state.players.each_ref().map(|p| p.vehicle.velocity_dx), [-0.1, 0.1]);
+        assert!(game_state.contact);
+        run(&mut game_state, 1);
+        assert!(game_state.contact);
+        for player in game_state.players.iter() {
+            assert!(player.health.unwrap().shields > expected - 1e-3);
+        }
+    }
+
+    #[test]
+    fn a_ship_rammed_to_pieces_goes_up_rather_than_bouncing() {
+        let (mut game_state, _) = with_shields();
+        let [one, two] = &mut game_state.players;
+        two.health = Some(Health { hull: 1.0, shields: 0.0, recharge_in: 0 });
+        two.vehicle.position_dx = one.vehicle.position_dx + 0.01;
+        two.vehicle.position_dy = one.vehicle.position_dy;
+        one.vehicle.velocity_dx = 0.1;
+        two.vehicle.velocity_dx = -0.1;
+        run(&mut game_state, 1);
+        assert!(game_state.players[PLAYER_ONE].alive);
+        assert!(!game_state.players[PLAYER_TWO].alive);
+        assert!(matches!(
+            game_state.events[..],
+            [SimEvent::Damaged { player: PLAYER_ONE, .. }, SimEvent::Rammed { player: PLAYER_TWO, .. }]
+        ));
+        // the survivor carries on as it was
+        assert_eq!(game_state.players[PLAYER_ONE].vehicle.velocity_dx, 0.1);
+        assert_eq!(game_state.scores, [1, 0]);
+    }
+
+    // the ships overlapping, and still closing, at an angle to the line between them
+    fn ships_meeting(collision: Collision) -> GameState {
+        let mut config = MatchConfig::default();
+        config.rules.collision = collision;
+        let mut game_state = create_game_state(3, config);
+        run(&mut game_state, COUNTDOWN_TICKS);
+        let [one, two] = &mut game_state.players;
+        (one.vehicle.position_dx, one.vehicle.position_dy) = (0.0, 0.0);
+        (two.vehicle.position_dx, two.vehicle.position_dy) = (0.01, 0.005);
+        (one.vehicle.velocity_dx, one.vehicle.velocity_dy) = (0.3, 0.05);
+        (two.vehicle.velocity_dx, two.vehicle.velocity_dy) = (-0.1, 0.02);
+        game_state
+    }
+
+    fn momentum(game_state: &GameState) -> [f32; 2] {
+        let [one, two] = game_state.players.each_ref().map(|p| p.vehicle);
+        [one.velocity_dx + two.velocity_dx, one.velocity_dy + two.velocity_dy]
+    }
+
+    fn energy(game_state: &GameState) -> f32 {
+        let [one, two] = game_state.players.each_ref().map(|p| p.vehicle);
+        let speed_squared = |v: Vehicle_100| v.velocity_dx * v.velocity_dx + v.velocity_dy * v.velocity_dy;
+        speed_squared(one) + speed_squared(two)
+    }
+
+    fn assert_near(left: [f32; 2], right: [f32; 2]) {
+        assert!((left[0] - right[0]).abs() < 1e-6 && (left[1] - right[1]).abs() < 1e-6, "{:?} != {:?}", left, right);
+    }
+
+    #[test]
+    fn ramming_destroys_both_ships_under_classic_rules() {
+        let mut game_state = ships_meeting(Collision::Destroy);
+        run(&mut game_state, 1);
+        assert!(game_state.players.iter().all(|p| !p.alive));
+        assert!(matches!(game_state.events[..], [SimEvent::VehicleCollision { .. }]));
+        assert_eq!(game_state.phase, RoundPhase::RoundOver);
+        assert_eq!(game_state.scores, [0, 0]);
+
+        // the wrecks neither collide again nor bounce
+        run(&mut game_state, 1);
+        assert!(game_state.events.is_empty());
+    }
+
+    #[test]
+    fn bouncing_ships_trade_momentum_without_losing_any() {
+        let mut game_state = ships_meeting(Collision::Bounce);
+        let velocities = game_state.players.each_ref().map(|p| [p.vehicle.velocity_dx, p.vehicle.velocity_dy]);
+        let (before, energy_before) = (momentum(&game_state), energy(&game_state));
+        run(&mut game_state, 1);
+        assert!(game_state.players.iter().all(|p| p.alive));
+        assert!(matches!(game_state.events[..], [SimEvent::Bounce { .. }]));
+        assert_near(momentum(&game_state), before);
+        assert!((energy(&game_state) - energy_before).abs() < 1e-6);
+        assert_ne!(game_state.players.each_ref().map(|p| [p.vehicle.velocity_dx, p.vehicle.velocity_dy]), velocities);
+
+        // once apart they only drift further apart, without knocking again
+        let apart = game_state.players.each_ref().map(|p| p.vehicle);
+        run(&mut game_state, 30);
+        assert!(!game_state.contact);
+        assert_eq!(game_state.players.each_ref().map(|p| p.vehicle.velocity_dx), apart.map(|v| v.velocity_dx));
+        assert_eq!(game_state.phase, RoundPhase::Playing);
+    }
+}
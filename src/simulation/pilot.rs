@@ -0,0 +1,214 @@
+// The computer's side in a game against it.  It sees what a player would, the state after
+// the last tick, and answers with the keys it would press.  Those are recorded like anyone
+// else's, so a replay of a game against the computer plays back without it.
+//
+// It turns its nose to where the other ship will be by the time a torpedo gets there,
+// fires when lined up and in range, and keeps itself moving at a modest speed.  When the
+// rules limit fuel or torpedoes it husbands them: it cruises slower, and holds its fire
+// for closer shots.  A torpedo about to hit it sends it into hyperspace, while jumping
+// is still the safer bet.
+
+use super::{Controls, GameState, RoundPhase, FIRE, HYPERSPACE, ROTATE_LEFT, ROTATE_RIGHT, THRUST};
+
+// Torpedoes leave the arena rather than wrap, so nothing much further is worth a shot.
+const FIRING_RANGE: f32 = 1.2;
+// and with a limited magazine
+const SHORT_FIRING_RANGE: f32 = 0.6;
+// ticks between shots, and shots of its own it lets be in flight at once
+const FIRE_INTERVAL: u32 = 20;
+const MAX_TORPEDOES: usize = 3;
+// arena widths a second, with unlimited fuel and then on a tank
+const CRUISE_SPEED: f32 = 0.25;
+const ECONOMY_SPEED: f32 = 0.12;
+// ticks between presses of the thrust key
+const THRUST_INTERVAL: u32 = 8;
+// how close one of the other ship's torpedoes gets before it jumps, and the most risk of
+// breaking up it'll take to get away
+const DANGER_RANGE: f32 = 0.08;
+const ACCEPTABLE_RISK: f32 = 0.5;
+
+pub fn computer_controls(game_state: &GameState, idx: usize) -> Controls {
+    let mut controls = Controls::default();
+    let (me, them) = (&game_state.players[idx], &game_state.players[1 - idx]);
+    let rules = &game_state.config.rules;
+    if game_state.phase != RoundPhase::Playing || !me.present() {
+        return controls;
+    }
+    if me.can_jump(rules)
+        && rules.hyperspace.risk_of(me.hyperspace.jumps + 1) < ACCEPTABLE_RISK
+        && under_fire(game_state, idx)
+    {
+        controls.press(HYPERSPACE);
+        return controls;
+    }
+    // nothing to aim at
+    if !them.present() {
+        return controls;
+    }
+    let (ship, target) = (&me.vehicle, &them.vehicle);
+
+    // where the target will be when a torpedo fired now reaches it; torpedoes carry the
+    // ship's own velocity, so only the difference matters
+    let (dx, dy) = (target.position_dx - ship.position_dx, target.position_dy - ship.position_dy);
+    let distance = (dx * dx + dy * dy).sqrt();
+    let flight = distance / rules.torpedo.speed;
+    let aim_dx = dx + (target.velocity_dx - ship.velocity_dx) * flight;
+    let aim_dy = dy + (target.velocity_dy - ship.velocity_dy) * flight;
+    let aim_length = (aim_dx * aim_dx + aim_dy * aim_dy).sqrt().max(f32::EPSILON);
+
+    let (hx, hy) = ship.heading();
+    let ahead = hx * aim_dx + hy * aim_dy > 0.0;
+    // positive when the aim point is anticlockwise of the nose
+    let off = (hx * aim_dy - hy * aim_dx) / aim_length;
+    // Sine of how far off the target it still counts as lined up; one turn step either
+    // way, so it doesn't hunt back and forth across the line.
+    let lined_up = ahead && off.abs() <= rules.ship.rotation_step;
+
+    if !lined_up {
+        controls.press(if off >= 0.0 { ROTATE_LEFT } else { ROTATE_RIGHT });
+    }
+
+    let range = if me.torpedoes.is_some() { SHORT_FIRING_RANGE } else { FIRING_RANGE };
+    let in_flight = game_state.torps_in_flight.iter().filter(|t| t.owner == idx).count();
+    if lined_up
+        && me.can_fire()
+        && distance < range
+        && in_flight < MAX_TORPEDOES
+        && game_state.tick.is_multiple_of(FIRE_INTERVAL)
+    {
+        controls.press(FIRE);
+    }
+
+    let cruise = if me.fuel.is_some() { ECONOMY_SPEED } else { CRUISE_SPEED };
+    let speed_squared = ship.velocity_dx * ship.velocity_dx + ship.velocity_dy * ship.velocity_dy;
+    if ahead
+        && me.can_thrust()
+        && speed_squared < cruise * cruise
+        && game_state.tick.is_multiple_of(THRUST_INTERVAL)
+    {
+        controls.press(THRUST);
+    }
+
+    controls
+}
+
+// whether one of the other ship's torpedoes is close and closing
+fn under_fire(game_state: &GameState, idx: usize) -> bool {
+    let ship = &game_state.players[idx].vehicle;
+    game_state.torps_in_flight.iter().filter(|t| t.owner != idx).any(|t| {
+        let torp = &t.vehicle;
+        let (dx, dy) = (ship.position_dx - torp.position_dx, ship.position_dy - torp.position_dy);
+        let closing = dx * (torp.velocity_dx - ship.velocity_dx) + dy * (torp.velocity_dy - ship.velocity_dy) > 0.0;
+        closing && dx * dx + dy * dy < DANGER_RANGE * DANGER_RANGE
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use cgmath::Rad;
+
+    use super::*;
+    use crate::simulation::{
+        create_game_state, update_game_state, MatchConfig, MatchResult, TickInput, Torpedo, PLAYER_ONE,
+        PLAYER_TWO,
+    };
+
+    // playing, with player two `dx` to the right of player one and both at rest
+    fn facing_off(theta: f32, dx: f32) -> GameState {
+        let mut game_state = create_game_state(3, MatchConfig::default());
+        game_state.phase = RoundPhase::Playing;
+        game_state.tick = FIRE_INTERVAL;
+        for player in game_state.players.iter_mut() {
+            player.vehicle.position_dx = 0.0;
+            player.vehicle.position_dy = 0.0;
+        }
+        game_state.players[PLAYER_ONE].vehicle.vifo_theta = Rad(theta);
+        game_state.players[PLAYER_TWO].vehicle.position_dx = dx;
+        game_state
+    }
+
+    #[test]
+    fn turns_the_short_way_towards_the_target() {
+        // nose a little above the target: turn clockwise
+        let controls = computer_controls(&facing_off(0.5, 0.5), PLAYER_ONE);
+        assert!(controls.pressed(ROTATE_RIGHT) && !controls.pressed(ROTATE_LEFT));
+        let controls = computer_controls(&facing_off(-0.5, 0.5), PLAYER_ONE);
+        assert!(controls.pressed(ROTATE_LEFT) && !controls.pressed(ROTATE_RIGHT));
+        assert!(!controls.pressed(FIRE));
+    }
+
+    #[test]
+    fn fires_when_lined_up_and_in_range() {
+        let controls = computer_controls(&facing_off(0.0, 0.5), PLAYER_ONE);
+        assert!(controls.pressed(FIRE));
+        assert!(!controls.pressed(ROTATE_LEFT) && !controls.pressed(ROTATE_RIGHT));
+
+        let mut out_of_range = facing_off(0.0, 0.5);
+        out_of_range.players[PLAYER_TWO].vehicle.position_dx = FIRING_RANGE + 0.1;
+        assert!(!computer_controls(&out_of_range, PLAYER_ONE).pressed(FIRE));
+    }
+
+    #[test]
+    fn saves_a_short_magazine_for_close_shots() {
+        let mut game_state = facing_off(0.0, 0.9);
+        assert!(computer_controls(&game_state, PLAYER_ONE).pressed(FIRE));
+        game_state.players[PLAYER_ONE].torpedoes = Some(4);
+        assert!(!computer_controls(&game_state, PLAYER_ONE).pressed(FIRE));
+        game_state.players[PLAYER_TWO].vehicle.position_dx = 0.5;
+        assert!(computer_controls(&game_state, PLAYER_ONE).pressed(FIRE));
+        game_state.players[PLAYER_ONE].torpedoes = Some(0);
+        assert!(!computer_controls(&game_state, PLAYER_ONE).pressed(FIRE));
+    }
+
+    #[test]
+    fn leaves_an_empty_tank_alone() {
+        let mut game_state = facing_off(0.0, 0.5);
+        game_state.tick = FIRE_INTERVAL * THRUST_INTERVAL;
+        assert!(computer_controls(&game_state, PLAYER_ONE).pressed(THRUST));
+        game_state.players[PLAYER_ONE].fuel = Some(0);
+        assert!(!computer_controls(&game_state, PLAYER_ONE).pressed(THRUST));
+    }
+
+    #[test]
+    fn jumps_away_from_a_torpedo_while_the_odds_are_good() {
+        // one of player two's, just short of player one and heading for it
+        let mut game_state = facing_off(0.0, 0.5);
+        let mut torp = game_state.players[PLAYER_ONE].vehicle;
+        torp.position_dx = 0.05;
+        torp.velocity_dx = -0.34;
+        game_state.torps_in_flight.push(Torpedo { vehicle: torp, owner: PLAYER_TWO, age: 30 });
+        assert!(computer_controls(&game_state, PLAYER_ONE).pressed(HYPERSPACE));
+
+        // not once it's gone past
+        let mut passed = game_state.clone();
+        passed.torps_in_flight[0].vehicle.position_dx = -0.05;
+        assert!(!computer_controls(&passed, PLAYER_ONE).pressed(HYPERSPACE));
+
+        // and not when the next jump is more likely to kill it than the torpedo
+        game_state.players[PLAYER_ONE].hyperspace.jumps = 2;
+        assert!(!computer_controls(&game_state, PLAYER_ONE).pressed(HYPERSPACE));
+    }
+
+    #[test]
+    fn sits_still_outside_play() {
+        let game_state = create_game_state(3, MatchConfig::default());
+        assert_eq!(computer_controls(&game_state, PLAYER_TWO), Controls::default());
+
+        let mut game_state = facing_off(0.0, 0.5);
+        game_state.players[PLAYER_TWO].alive = false;
+        assert_eq!(computer_controls(&game_state, PLAYER_ONE), Controls::default());
+    }
+
+    #[test]
+    fn two_computers_settle_a_match_before_the_clock_does() {
+        let mut game_state = create_game_state(7, MatchConfig::default());
+        while game_state.phase != RoundPhase::MatchOver {
+            let input = TickInput {
+                players: [computer_controls(&game_state, PLAYER_ONE), computer_controls(&game_state, PLAYER_TWO)],
+            };
+            update_game_state(&mut game_state, &input);
+        }
+        assert!(matches!(game_state.result, MatchResult::Winner(_)));
+        assert!(game_state.scores.iter().sum::<u32>() >= game_state.config.rounds_to_win());
+    }
+}
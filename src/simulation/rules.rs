@@ -0,0 +1,369 @@
+// The numbers the game is played by: how ships turn and thrust, how torpedoes fly, what
+// ramming does, how much punishment a ship takes, how safe hyperspace is, how hard the
+// star pulls, how long rounds last and how a match is won.  They travel with the match
+// config, so a replay plays back under the rules it was recorded with.
+//
+// Rules files are RON, or JSON with a `.json` extension.  Anything left out takes its
+// value from the classic rules, so a file need only say what it changes:
+//
+//     (
+//         name: "Heavy torpedoes",
+//         torpedo: (speed: 0.2, radius: 0.012),
+//     )
+//
+// A misspelt field or a value out of range is refused with the field's name, rather than
+// quietly played with.  The bundled presets live in src/rules.
+
+use std::fmt;
+use std::ops::RangeInclusive;
+use std::sync::OnceLock;
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct GameRules {
+    pub name: String,
+    pub ship: ShipRules,
+    pub torpedo: TorpedoRules,
+    pub collision: Collision,
+    // None for the classic game, where any hit destroys a ship
+    pub damage: Option<DamageRules>,
+    pub hyperspace: HyperspaceRules,
+    pub gravity: GravityRules,
+    pub round: RoundRules,
+    pub scoring: ScoringRules,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ShipRules {
+    // radians a tick the turn keys swing the nose through
+    pub rotation_step: f32,
+    // arena widths a second added along the nose per thrust
+    pub thrust: f32,
+    // ticks of thrust in a full tank, filled at the start of every round; None for no
+    // limit
+    pub fuel: Option<u32>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct TorpedoRules {
+    // arena widths a second, on top of the firing ship's velocity
+    pub speed: f32,
+    pub radius: f32,
+    // A fresh torpedo starts on top of the ship that fired it, and can't hurt it until
+    // this many ticks have passed.
+    pub arming_ticks: u32,
+    // torpedoes a ship carries into each round; None for no limit
+    pub magazine: Option<u32>,
+}
+
+// Ships that can take a few hits.  Damage goes to the shields first and the hull after
+// them, and a ship is lost when its hull is gone.  Hits do damage in proportion to how
+// fast the two things met, in arena widths a second.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct DamageRules {
+    pub hull: f32,
+    pub shields: f32,
+    // shield energy back a second, once the ship has held its fire for
+    // `recharge_delay_ticks`
+    pub shield_recharge: f32,
+    pub recharge_delay_ticks: u32,
+    // damage for each arena width a second of impact speed; a bounce hurts both ships
+    pub torpedo_damage: f32,
+    pub collision_damage: f32,
+}
+
+// What happens when the two ships touch.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Collision {
+    // both are lost, as in the original game; damage rules can't be used with it
+    #[default]
+    Destroy,
+    // They knock apart like billiard balls, trading momentum along the line between
+    // their centres.  Under damage rules the knock costs both of them.
+    Bounce,
+}
+
+// The panic button: the ship vanishes, and comes back somewhere else at random a moment
+// later, if it comes back at all.  Every jump in a round is riskier than the last.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct HyperspaceRules {
+    // jumps a ship gets each round; 0 turns hyperspace off
+    pub jumps: u32,
+    // ticks spent out of the arena
+    pub duration_ticks: u32,
+    // ticks after coming back before the next jump
+    pub cooldown_ticks: u32,
+    // chance of breaking up on the way back from the first jump of a round, and how much
+    // it grows with each jump after that
+    pub risk: f32,
+    pub risk_per_jump: f32,
+}
+
+// A star at the centre of the arena pulls on ships and torpedoes alike.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct GravityRules {
+    // 0 for none; otherwise the acceleration at a distance of one arena unit
+    pub strength: f32,
+    // distance inside which the pull stops growing, so nothing is flung off at near
+    // infinite speed by passing through the centre
+    pub softening: f32,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RoundRules {
+    // ships held still before each round
+    pub countdown_ticks: u32,
+    // each round's time limit; when it runs out nobody scores
+    pub round_ticks: u32,
+    // the wreckage playing out once a round is decided
+    pub round_over_ticks: u32,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ScoringRules {
+    // rounds in the match; winning more than half of them takes it
+    pub best_of: u32,
+    // whether ships going down together both take the round, rather than neither
+    pub shared_rounds: bool,
+}
+
+impl Default for GameRules {
+    fn default() -> Self {
+        Self {
+            name: String::from("Classic"),
+            ship: ShipRules::default(),
+            torpedo: TorpedoRules::default(),
+            collision: Collision::Destroy,
+            damage: None,
+            hyperspace: HyperspaceRules::default(),
+            gravity: GravityRules::default(),
+            round: RoundRules::default(),
+            scoring: ScoringRules::default(),
+        }
+    }
+}
+
+impl Default for ShipRules {
+    fn default() -> Self {
+        Self { rotation_step: 0.1, thrust: 0.08, fuel: None }
+    }
+}
+
+impl Default for TorpedoRules {
+    fn default() -> Self {
+        Self { speed: 0.34, radius: 0.005, arming_ticks: 20, magazine: None }
+    }
+}
+
+impl Default for DamageRules {
+    fn default() -> Self {
+        // a torpedo into a ship at rest takes most of the shields, and four finish it off
+        Self {
+            hull: 100.0,
+            shields: 60.0,
+            shield_recharge: 6.0,
+            recharge_delay_ticks: 90,
+            torpedo_damage: 140.0,
+            collision_damage: 120.0,
+        }
+    }
+}
+
+impl Default for HyperspaceRules {
+    fn default() -> Self {
+        // one safe jump in ten, then three in ten and so on
+        Self { jumps: 3, duration_ticks: 50, cooldown_ticks: 250, risk: 0.1, risk_per_jump: 0.2 }
+    }
+}
+
+impl HyperspaceRules {
+    // the chance of not coming back from a round's `jump`th jump, counting from 1
+    pub fn risk_of(&self, jump: u32) -> f32 {
+        (self.risk + self.risk_per_jump * jump.saturating_sub(1) as f32).min(1.0)
+    }
+}
+
+impl Default for GravityRules {
+    fn default() -> Self {
+        Self { strength: 0.0, softening: 0.1 }
+    }
+}
+
+impl Default for RoundRules {
+    fn default() -> Self {
+        // a minute to play, with a couple of seconds either side
+        Self { countdown_ticks: 120, round_ticks: 3750, round_over_ticks: 150 }
+    }
+}
+
+impl Default for ScoringRules {
+    fn default() -> Self {
+        Self { best_of: 5, shared_rounds: false }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum RulesError {
+    Syntax(String),
+    OutOfRange { field: &'static str, value: f64, allowed: RangeInclusive<f64> },
+    // two settings that can't be played together
+    Conflict(&'static str),
+}
+
+impl fmt::Display for RulesError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RulesError::Syntax(e) => write!(f, "rules file can't be read: {}", e),
+            RulesError::OutOfRange { field, value, allowed } => write!(
+                f,
+                "{} is {}, but must be from {} to {}",
+                field,
+                value,
+                allowed.start(),
+                allowed.end()
+            ),
+            RulesError::Conflict(why) => write!(f, "{}", why),
+        }
+    }
+}
+
+impl GameRules {
+    // Reads a rules file, RON unless its name ends in .json.
+    pub fn parse(file_name: &str, text: &str) -> Result<Self, RulesError> {
+        if file_name.to_lowercase().ends_with(".json") {
+            Self::from_json(text)
+        } else {
+            Self::from_ron(text)
+        }
+    }
+
+    pub fn from_ron(text: &str) -> Result<Self, RulesError> {
+        let rules: Self = ron::from_str(text).map_err(|e| RulesError::Syntax(e.to_string()))?;
+        rules.validate()?;
+        Ok(rules)
+    }
+
+    pub fn from_json(text: &str) -> Result<Self, RulesError> {
+        let rules: Self = serde_json::from_str(text).map_err(|e| RulesError::Syntax(e.to_string()))?;
+        rules.validate()?;
+        Ok(rules)
+    }
+
+    pub fn validate(&self) -> Result<(), RulesError> {
+        check("ship.rotation_step", self.ship.rotation_step, 0.001..=1.5)?;
+        check("ship.thrust", self.ship.thrust, 0.0..=1.0)?;
+        if let Some(fuel) = self.ship.fuel {
+            check("ship.fuel", fuel, 1.0..=100_000.0)?;
+        }
+        check("torpedo.speed", self.torpedo.speed, 0.01..=5.0)?;
+        check("torpedo.radius", self.torpedo.radius, 0.001..=0.1)?;
+        check("torpedo.arming_ticks", self.torpedo.arming_ticks, 0.0..=600.0)?;
+        if let Some(magazine) = self.torpedo.magazine {
+            check("torpedo.magazine", magazine, 1.0..=1000.0)?;
+        }
+        if let Some(damage) = self.damage {
+            check("damage.hull", damage.hull, 1.0..=10_000.0)?;
+            check("damage.shields", damage.shields, 0.0..=10_000.0)?;
+            check("damage.shield_recharge", damage.shield_recharge, 0.0..=10_000.0)?;
+            check("damage.recharge_delay_ticks", damage.recharge_delay_ticks, 0.0..=3750.0)?;
+            check("damage.torpedo_damage", damage.torpedo_damage, 0.0..=100_000.0)?;
+            check("damage.collision_damage", damage.collision_damage, 0.0..=100_000.0)?;
+            // Destroy would wreck both ships at the first touch, whatever their shields
+            if self.collision == Collision::Destroy {
+                return Err(RulesError::Conflict("damage needs collision: Bounce, or ramming would ignore it"));
+            }
+        }
+        check("hyperspace.jumps", self.hyperspace.jumps, 0.0..=99.0)?;
+        check("hyperspace.duration_ticks", self.hyperspace.duration_ticks, 1.0..=600.0)?;
+        check("hyperspace.cooldown_ticks", self.hyperspace.cooldown_ticks, 0.0..=3750.0)?;
+        check("hyperspace.risk", self.hyperspace.risk, 0.0..=1.0)?;
+        check("hyperspace.risk_per_jump", self.hyperspace.risk_per_jump, 0.0..=1.0)?;
+        check("gravity.strength", self.gravity.strength, 0.0..=1.0)?;
+        check("gravity.softening", self.gravity.softening, 0.01..=1.0)?;
+        // up to twenty seconds either side of a round of up to ten minutes
+        check("round.countdown_ticks", self.round.countdown_ticks, 0.0..=1250.0)?;
+        check("round.round_ticks", self.round.round_ticks, 1.0..=37500.0)?;
+        check("round.round_over_ticks", self.round.round_over_ticks, 0.0..=1250.0)?;
+        check("scoring.best_of", self.scoring.best_of, 1.0..=99.0)?;
+        Ok(())
+    }
+}
+
+fn check(field: &'static str, value: impl Into<f64>, allowed: RangeInclusive<f64>) -> Result<(), RulesError> {
+    let value = value.into();
+    match allowed.contains(&value) {
+        true => Ok(()),
+        false => Err(RulesError::OutOfRange { field, value, allowed }),
+    }
+}
+
+// The rules that come with the game, classic first.
+pub fn presets() -> &'static [GameRules] {
+    static PRESETS: OnceLock<Vec<GameRules>> = OnceLock::new();
+    PRESETS.get_or_init(|| {
+        [
+            include_str!("../rules/classic.ron"),
+            include_str!("../rules/star.ron"),
+            include_str!("../rules/shields.ron"),
+            include_str!("../rules/sudden_death.ron"),
+        ]
+        .iter()
+        .map(|text| GameRules::from_ron(text).unwrap_or_else(|e| panic!("a bundled rules file is broken: {}", e)))
+        .collect()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn presets_load_and_classic_is_the_default() {
+        let presets = presets();
+        assert_eq!(presets[0], GameRules::default());
+        let names: Vec<&str> = presets.iter().map(|rules| rules.name.as_str()).collect();
+        assert_eq!(names, ["Classic", "Star", "Shields", "Sudden death"]);
+    }
+
+    #[test]
+    fn a_file_need_only_say_what_it_changes() {
+        let ron = r#"(name: "Heavy", torpedo: (speed: 0.2, magazine: Some(8)), scoring: (shared_rounds: true))"#;
+        let json = r#"{ "name": "Heavy", "torpedo": { "speed": 0.2, "magazine": 8 }, "scoring": { "shared_rounds": true } }"#;
+        let rules = GameRules::parse("heavy.ron", ron).unwrap();
+        assert_eq!(GameRules::parse("heavy.JSON", json), Ok(rules.clone()));
+        assert_eq!(rules.torpedo.speed, 0.2);
+        assert_eq!(rules.torpedo.radius, TorpedoRules::default().radius);
+        assert_eq!(rules.torpedo.magazine, Some(8));
+        assert!(rules.scoring.shared_rounds);
+        assert_eq!(rules.ship, ShipRules::default());
+        assert_eq!(rules.collision, Collision::Destroy);
+        assert_eq!(GameRules::from_ron("(collision: Bounce)").unwrap().collision, Collision::Bounce);
+        assert_eq!(GameRules::from_json(r#"{ "collision": "Bounce" }"#).unwrap().collision, Collision::Bounce);
+    }
+
+    #[test]
+    fn bad_files_are_refused_with_the_field_at_fault() {
+        let error = GameRules::from_ron("(ship: (thrust: 3.0))").unwrap_err();
+        assert_eq!(error.to_string(), "ship.thrust is 3, but must be from 0 to 1");
+        let error = GameRules::from_json(r#"{ "scoring": { "best_of": 0 } }"#).unwrap_err();
+        assert!(matches!(error, RulesError::OutOfRange { field: "scoring.best_of", .. }));
+        let error = GameRules::from_ron("(damage: Some((hull: 0)))").unwrap_err();
+        assert!(matches!(error, RulesError::OutOfRange { field: "damage.hull", .. }));
+        let error = GameRules::from_ron("(damage: Some(()))").unwrap_err();
+        assert!(matches!(error, RulesError::Conflict(_)));
+        let error = GameRules::from_ron("(ship: (fuel: Some(0)))").unwrap_err();
+        assert!(matches!(error, RulesError::OutOfRange { field: "ship.fuel", .. }));
+        // misspelt
+        assert!(matches!(GameRules::from_ron("(torpedo: (sped: 0.2))"), Err(RulesError::Syntax(_))));
+        assert!(matches!(GameRules::from_json("{ \"ship\": "), Err(RulesError::Syntax(_))));
+    }
+}
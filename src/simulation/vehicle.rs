@@ -0,0 +1,88 @@
+use cgmath::Rad;
+
+use super::fmath;
+
+// Ships and torpedoes share one kinematic description, as in the original game_* components.
+#[allow(non_camel_case_types)]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Vehicle_100 {
+    pub position_dx: f32, // raw displacement in x, y
+    pub position_dy: f32,
+    // vehicle inertial frame orientation
+    pub vifo_theta: Rad<f32>,
+    pub velocity_dx: f32,
+    pub velocity_dy: f32,
+}
+
+impl Vehicle_100 {
+    // Unit vector along the nose.
+    pub fn heading(&self) -> (f32, f32) {
+        let (s, c) = fmath::sin_cos(self.vifo_theta.0);
+        (c, s)
+    }
+
+    pub fn advance(&mut self, delta_scalar: f32) {
+        self.position_dx += self.velocity_dx * delta_scalar;
+        self.position_dy += self.velocity_dy * delta_scalar;
+    }
+
+    // The arena is a torus: leaving one edge re-enters at the other.
+    pub fn wrap(&mut self) {
+        if self.position_dx < -1.0 {
+            self.position_dx += 2.0;
+        }
+        if self.position_dx > 1.0 {
+            self.position_dx -= 2.0;
+        }
+        if self.position_dy < -1.0 {
+            self.position_dy += 2.0;
+        }
+        if self.position_dy > 1.0 {
+            self.position_dy -= 2.0;
+        }
+    }
+
+    pub fn out_of_bounds(&self) -> bool {
+        !(-1.0..=1.0).contains(&self.position_dx) || !(-1.0..=1.0).contains(&self.position_dy)
+    }
+
+    // the speed of one as seen from the other
+    pub fn speed_relative_to(&self, other: &Vehicle_100) -> f32 {
+        let dx = self.velocity_dx - other.velocity_dx;
+        let dy = self.velocity_dy - other.velocity_dy;
+        (dx * dx + dy * dy).sqrt()
+    }
+
+    // An elastic knock between two bodies of the same mass: the parts of their velocities
+    // along the line between their centres are swapped, which keeps both momentum and
+    // energy.  Nothing happens unless they're closing, so two still overlapping after a
+    // bounce drift apart instead of trading back.  True if they bounced.
+    pub fn bounce_off(&mut self, other: &mut Vehicle_100) -> bool {
+        let (rvx, rvy) = (self.velocity_dx - other.velocity_dx, self.velocity_dy - other.velocity_dy);
+        let (mut nx, mut ny) = (other.position_dx - self.position_dx, other.position_dy - self.position_dy);
+        if nx == 0.0 && ny == 0.0 {
+            // dead on top of each other, so meet head on
+            (nx, ny) = (rvx, rvy);
+        }
+        let length = (nx * nx + ny * ny).sqrt();
+        if length == 0.0 {
+            return false;
+        }
+        let (nx, ny) = (nx / length, ny / length);
+        let closing = rvx * nx + rvy * ny;
+        if closing <= 0.0 {
+            return false;
+        }
+        self.velocity_dx -= closing * nx;
+        self.velocity_dy -= closing * ny;
+        other.velocity_dx += closing * nx;
+        other.velocity_dy += closing * ny;
+        true
+    }
+
+    pub fn distance_squared(&self, other: &Vehicle_100) -> f32 {
+        let dx = self.position_dx - other.position_dx;
+        let dy = self.position_dy - other.position_dy;
+        dx * dx + dy * dy
+    }
+}